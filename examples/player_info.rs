@@ -13,7 +13,7 @@ use conrod_core::widget::primitive::image::Image;
 use cardgame_widgets::custom_widget::player_info::list::List;
 use cardgame_widgets::custom_widget::player_info::item::IconStruct;
 use cardgame_widgets::sprite::SpriteInfo;
-use cardgame_widgets::text::get_font_size_hn;
+use cardgame_widgets::text::{get_font_size_hn, fit_font_size_wrapped, font_of};
 use std::time::Instant;
 
 widget_ids! {
@@ -211,13 +211,17 @@ fn set_widgets(ui: &mut conrod_core::UiCell,
                     .down_from(ids.overlay_top, 0.0)
                     .set(ids.overlay2, ui);
                 _image.wh([20.0, 20.0]).mid_left_of(ids.overlay2).set(ids.overlay_image, ui);
-                let fontsize = get_font_size_hn(_dim[1], 4.0);
+                let text_w = _dim[0] - 20.0;
+                let fontsize = match font_of(ui, None) {
+                    Some(font) => fit_font_size_wrapped(font, _desc, text_w, _dim[1], 1.0),
+                    None => get_font_size_hn(_dim[1], 4.0),
+                };
                 widget::Text::new(&_desc)
                     .font_size(fontsize)
                     .color(default_color.plain_contrast())
                     .align_middle_y_of(ids.overlay_image)
                     .right_from(ids.overlay_image, 0.0)
-                    .w(_dim[0] - 20.0)
+                    .w(text_w)
                     .h_of(ids.overlay2)
                     .set(ids.overlay_text, ui);
             }
@@ -21,6 +21,8 @@ widget_ids! {
 }
 pub struct App {
     watch: bool,
+    /// Whether to play the shuffle again from the start.
+    replay: bool,
     frame: i32,
 }
 #[derive(Clone)]
@@ -57,6 +59,7 @@ fn main() {
     let sixteen_ms = std::time::Duration::from_millis(800);
     let mut app = App {
         watch: true,
+        replay: false,
         frame: 0,
     };
 
@@ -162,20 +165,24 @@ fn set_widgets(ui: &mut conrod_core::UiCell,
     let back_rect = Rect::from_corners([670.0, 70.0], [1130.0, 850.0]);
     widget::Canvas::new().color(color::LIGHT_BLUE).set(ids.master, ui);
     if _app.watch {
-        _app.watch = Shuffle::new(vec![Image::new(rust_logo),
-                                       Image::new(green_logo),
-                                       Image::new(rust_logo)],
-                                  Image::new(back_logo).source_rectangle(back_rect))
-                .give_out(vec![0, 1, 2])
-                .mid_left_of(ids.master)
-                .w(400.0)
-                .set(ids.listview, ui);
+        let mut shuffle = Shuffle::new(vec![Image::new(rust_logo),
+                                            Image::new(green_logo),
+                                            Image::new(rust_logo)],
+                                       Image::new(back_logo).source_rectangle(back_rect))
+                .give_out(vec![0, 1, 2]);
+        shuffle.restart = _app.replay;
+        _app.replay = false;
+        _app.watch = !shuffle.mid_left_of(ids.master)
+                              .w(400.0)
+                              .set(ids.listview, ui)
+                              .is_finished();
 
     }
     if !_app.watch {
         _app.frame += 1;
         if _app.frame > 10 {
             _app.watch = true;
+            _app.replay = true;
             _app.frame = 0;
         }
     }
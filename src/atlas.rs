@@ -0,0 +1,362 @@
+//! Loading sprite sheets from TexturePacker ("hash" and "array") and Aseprite JSON exports.
+//!
+//! Frame rectangles in the JSON file are given in pixels with the origin at the top left of the
+//! image. The `Spriteable` values produced here are converted to the bottom-left origin that
+//! `spriteable_rect` and `widget::Image::source_rectangle` expect.
+use serde_json::{self, Value, Map};
+use sprite::{Clip, FrameList, SpriteInfo, Spriteable};
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use std::time::Duration;
+
+/// A single named frame of an `Atlas`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AtlasFrame {
+    pub name: String,
+    /// Left edge in pixels, measured from the left of the image.
+    pub x: f64,
+    /// Top edge in pixels, measured from the top of the image.
+    pub y: f64,
+    pub w: f64,
+    pub h: f64,
+    /// Aseprite exports carry a duration for every frame.
+    pub duration: Option<Duration>,
+}
+
+/// The frames of a sprite sheet together with the size of the image they were cut from.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Atlas {
+    /// The image file name recorded in the export's `meta` block.
+    pub image: Option<String>,
+    /// Width and height of the whole image in pixels.
+    pub size: (f64, f64),
+    /// Frames in the order they appear in the file.
+    pub frames: Vec<AtlasFrame>,
+    /// Aseprite `frameTags`, as ranges into `frames`.
+    pub tags: Vec<Clip>,
+}
+
+/// Everything that can go wrong while loading an atlas or checking a sprite against its image.
+#[derive(Debug)]
+pub enum AtlasError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    /// A required field is missing or has the wrong type. Holds the path of the field.
+    Malformed(String),
+    /// The export packed this frame rotated, which a source rectangle can't express.
+    Rotated(String),
+    /// The frame's rectangle does not lie inside the image.
+    OutOfBounds {
+        name: String,
+        rect: [f64; 4],
+        size: (f64, f64),
+    },
+    /// No frame with this name exists in the atlas.
+    UnknownFrame(String),
+    /// The frames can't be described by a uniform `SpriteInfo` grid.
+    NotAGrid(String),
+    /// The atlas or the requested selection has no frames.
+    Empty,
+}
+
+impl fmt::Display for AtlasError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AtlasError::Io(ref e) => write!(f, "could not read atlas: {}", e),
+            AtlasError::Json(ref e) => write!(f, "invalid atlas json: {}", e),
+            AtlasError::Malformed(ref field) => write!(f, "missing or invalid field `{}`", field),
+            AtlasError::Rotated(ref name) => write!(f, "frame `{}` is rotated", name),
+            AtlasError::OutOfBounds { ref name, rect, size } => {
+                write!(f,
+                       "frame `{}` ({}, {}, {}x{}) lies outside the {}x{} image",
+                       name,
+                       rect[0],
+                       rect[1],
+                       rect[2],
+                       rect[3],
+                       size.0,
+                       size.1)
+            }
+            AtlasError::UnknownFrame(ref name) => write!(f, "no frame named `{}`", name),
+            AtlasError::NotAGrid(ref name) => {
+                write!(f, "frames starting at `{}` do not form a uniform grid", name)
+            }
+            AtlasError::Empty => write!(f, "no frames"),
+        }
+    }
+}
+
+impl Error for AtlasError {}
+
+impl From<io::Error> for AtlasError {
+    fn from(e: io::Error) -> Self {
+        AtlasError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for AtlasError {
+    fn from(e: serde_json::Error) -> Self {
+        AtlasError::Json(e)
+    }
+}
+
+impl AtlasFrame {
+    /// The frame as a one cell `SpriteInfo`, in the bottom-left origin used by `spriteable_rect`.
+    pub fn sprite(&self, image_h: f64) -> SpriteInfo {
+        SpriteInfo {
+            first: (self.x, image_h - self.y),
+            num_in_row: 1,
+            num_in_col: 1,
+            w_h: (self.w, self.h),
+            pad: (0.0, 0.0, 0.0, 0.0),
+        }
+    }
+}
+
+impl Atlas {
+    /// Parse a TexturePacker or Aseprite export held in memory.
+    ///
+    /// Both the "hash" layout (`frames` is an object keyed by frame name) and the "array" layout
+    /// (`frames` is a list of objects with a `filename`) are accepted.
+    pub fn from_json(json: &str) -> Result<Atlas, AtlasError> {
+        let root: Value = serde_json::from_str(json)?;
+        let meta = root.get("meta").ok_or_else(|| malformed("meta"))?;
+        let size = meta.get("size").ok_or_else(|| malformed("meta.size"))?;
+        let size = (number(size, "w", "meta.size")?, number(size, "h", "meta.size")?);
+        let image = meta.get("image").and_then(Value::as_str).map(str::to_owned);
+        let frames = match root.get("frames") {
+            Some(&Value::Object(ref map)) => {
+                let mut frames = Vec::with_capacity(map.len());
+                for (name, value) in map.iter() {
+                    frames.push(parse_frame(name.clone(), value)?);
+                }
+                frames
+            }
+            Some(&Value::Array(ref list)) => {
+                let mut frames = Vec::with_capacity(list.len());
+                for (i, value) in list.iter().enumerate() {
+                    let name = value.get("filename")
+                        .and_then(Value::as_str)
+                        .ok_or_else(|| malformed(&format!("frames[{}].filename", i)))?;
+                    frames.push(parse_frame(name.to_owned(), value)?);
+                }
+                frames
+            }
+            _ => return Err(malformed("frames")),
+        };
+        let mut tags = vec![];
+        if let Some(list) = meta.get("frameTags").and_then(Value::as_array) {
+            for (i, tag) in list.iter().enumerate() {
+                let path = format!("meta.frameTags[{}]", i);
+                let name = tag.get("name")
+                    .and_then(Value::as_str)
+                    .ok_or_else(|| malformed(&format!("{}.name", path)))?;
+                let from = number(tag, "from", &path)? as usize;
+                let to = number(tag, "to", &path)? as usize;
+                if from > to || to >= frames.len() {
+                    return Err(malformed(&path));
+                }
+                tags.push(Clip {
+                              name: name.to_owned(),
+                              frames: from..to + 1,
+                          });
+            }
+        }
+        let atlas = Atlas {
+            image: image,
+            size: size,
+            frames: frames,
+            tags: tags,
+        };
+        for frame in atlas.frames.iter() {
+            atlas.check_bounds(frame)?;
+        }
+        Ok(atlas)
+    }
+
+    /// Parse an export from any reader.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Atlas, AtlasError> {
+        let mut json = String::new();
+        reader.read_to_string(&mut json)?;
+        Atlas::from_json(&json)
+    }
+
+    /// Load an export from a file on disk.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Atlas, AtlasError> {
+        Atlas::from_reader(File::open(path)?)
+    }
+
+    /// Look up a frame by name.
+    pub fn frame(&self, name: &str) -> Result<&AtlasFrame, AtlasError> {
+        self.frames
+            .iter()
+            .find(|f| f.name == name)
+            .ok_or_else(|| AtlasError::UnknownFrame(name.to_owned()))
+    }
+
+    /// A single frame as a `Spriteable`, e.g. for `BackCardView` or `CardViewPartial` with
+    /// `card_index` 0.
+    pub fn sprite(&self, name: &str) -> Result<SpriteInfo, AtlasError> {
+        self.frame(name).map(|f| f.sprite(self.size.1))
+    }
+
+    /// Describe the named frames, in order, as one `SpriteInfo` grid so that `FullCycleSprite`
+    /// or a spinner can step through them by index.
+    ///
+    /// The frames must share the same size, be laid out row by row with a constant step and fill
+    /// every cell of the grid.
+    pub fn grid(&self, names: &[&str]) -> Result<SpriteInfo, AtlasError> {
+        let frames = names.iter().map(|n| self.frame(n)).collect::<Result<Vec<_>, _>>()?;
+        grid_of(&frames, self.size.1)
+    }
+
+    /// Like `grid`, over every frame whose name starts with `prefix`, in file order.
+    pub fn grid_with_prefix(&self, prefix: &str) -> Result<SpriteInfo, AtlasError> {
+        let frames = self.frames.iter().filter(|f| f.name.starts_with(prefix)).collect::<Vec<_>>();
+        grid_of(&frames, self.size.1)
+    }
+
+    /// Every frame in the file as one grid.
+    pub fn whole_grid(&self) -> Result<SpriteInfo, AtlasError> {
+        let frames = self.frames.iter().collect::<Vec<_>>();
+        grid_of(&frames, self.size.1)
+    }
+
+    /// Every frame in the file as a `FrameList`, with the Aseprite tags as its clips.
+    ///
+    /// Unlike `grid` this works for frames of different sizes anywhere on the sheet.
+    pub fn frame_list(&self) -> Result<FrameList, AtlasError> {
+        if self.frames.is_empty() {
+            return Err(AtlasError::Empty);
+        }
+        Ok(FrameList {
+               frames: self.frames.iter().map(|f| corners(f, self.size.1)).collect(),
+               clips: self.tags.clone(),
+               durations: self.frames.iter().map(|f| f.duration).collect(),
+           })
+    }
+
+    /// The named frames, in order, as a `FrameList` without clips.
+    pub fn frame_list_of(&self, names: &[&str]) -> Result<FrameList, AtlasError> {
+        if names.is_empty() {
+            return Err(AtlasError::Empty);
+        }
+        let mut frames = Vec::with_capacity(names.len());
+        let mut durations = Vec::with_capacity(names.len());
+        for name in names {
+            let frame = self.frame(name)?;
+            frames.push(corners(frame, self.size.1));
+            durations.push(frame.duration);
+        }
+        Ok(FrameList {
+               frames: frames,
+               clips: vec![],
+               durations: durations,
+           })
+    }
+
+    fn check_bounds(&self, frame: &AtlasFrame) -> Result<(), AtlasError> {
+        check_rect(&frame.name, [frame.x, frame.y, frame.w, frame.h], self.size)
+    }
+}
+
+/// Check that every cell of a `Spriteable` lies inside an image of `image_size` pixels.
+///
+/// Useful for hand-typed `SpriteInfo`s, whose mistakes otherwise show up as garbage on screen.
+pub fn validate<H: Spriteable>(sprite: &H, image_size: (f64, f64)) -> Result<(), AtlasError> {
+    let (w, h) = sprite.w_h();
+    let (first_x, first_y) = sprite.first();
+    for row in 0..sprite.num_in_col() {
+        for col in 0..sprite.num_in_row() {
+            let x = first_x + col as f64 * w;
+            let top = image_size.1 - (first_y - row as f64 * h);
+            let name = format!("cell {}", row as usize * sprite.num_in_row() as usize + col as usize);
+            check_rect(&name, [x, top, w, h], image_size)?;
+        }
+    }
+    Ok(())
+}
+
+fn grid_of(frames: &[&AtlasFrame], image_h: f64) -> Result<SpriteInfo, AtlasError> {
+    let first = match frames.first() {
+        Some(first) => *first,
+        None => return Err(AtlasError::Empty),
+    };
+    let not_a_grid = || AtlasError::NotAGrid(first.name.clone());
+    if frames.iter().any(|f| f.w != first.w || f.h != first.h) {
+        return Err(not_a_grid());
+    }
+    let num_in_row = frames.iter().take_while(|f| f.y == first.y).count();
+    let step_x = match frames.get(1) {
+        Some(second) if num_in_row > 1 => second.x - first.x,
+        _ => first.w,
+    };
+    let step_y = match frames.get(num_in_row) {
+        Some(below) => below.y - first.y,
+        None => first.h,
+    };
+    if step_x < first.w || step_y < first.h || frames.len() % num_in_row != 0 {
+        return Err(not_a_grid());
+    }
+    for (i, f) in frames.iter().enumerate() {
+        let (col, row) = ((i % num_in_row) as f64, (i / num_in_row) as f64);
+        if f.x != first.x + col * step_x || f.y != first.y + row * step_y {
+            return Err(not_a_grid());
+        }
+    }
+    Ok(SpriteInfo {
+        first: (first.x, image_h - first.y),
+        num_in_row: num_in_row as u16,
+        num_in_col: (frames.len() / num_in_row) as u16,
+        w_h: (step_x, step_y),
+        pad: (0.0, step_x - first.w, 0.0, step_y - first.h),
+    })
+}
+
+fn corners(frame: &AtlasFrame, image_h: f64) -> ([f64; 2], [f64; 2]) {
+    ([frame.x, image_h - frame.y], [frame.x + frame.w, image_h - frame.y - frame.h])
+}
+
+fn parse_frame(name: String, value: &Value) -> Result<AtlasFrame, AtlasError> {
+    let path = format!("frames.{}.frame", name);
+    let rect = value.get("frame").ok_or_else(|| malformed(&path))?;
+    if value.get("rotated").and_then(Value::as_bool).unwrap_or(false) {
+        return Err(AtlasError::Rotated(name));
+    }
+    let duration = value.get("duration").and_then(Value::as_u64).map(Duration::from_millis);
+    Ok(AtlasFrame {
+        x: number(rect, "x", &path)?,
+        y: number(rect, "y", &path)?,
+        w: number(rect, "w", &path)?,
+        h: number(rect, "h", &path)?,
+        name: name,
+        duration: duration,
+    })
+}
+
+fn number(value: &Value, key: &str, path: &str) -> Result<f64, AtlasError> {
+    value.as_object()
+        .and_then(|o: &Map<String, Value>| o.get(key))
+        .and_then(Value::as_f64)
+        .ok_or_else(|| malformed(&format!("{}.{}", path, key)))
+}
+
+fn check_rect(name: &str, rect: [f64; 4], size: (f64, f64)) -> Result<(), AtlasError> {
+    let [x, y, w, h] = rect;
+    if w <= 0.0 || h <= 0.0 || x < 0.0 || y < 0.0 || x + w > size.0 || y + h > size.1 {
+        Err(AtlasError::OutOfBounds {
+            name: name.to_owned(),
+            rect: rect,
+            size: size,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+fn malformed(path: &str) -> AtlasError {
+    AtlasError::Malformed(path.to_owned())
+}
@@ -0,0 +1,101 @@
+//! Time sources for animated widgets.
+//!
+//! Widgets that animate take a `&Clock` through a `.clock(..)` builder method and keep the
+//! `Instant` their animation started at in their state. Without one they use `SYSTEM_CLOCK`.
+//! A `ManualClock` only moves when told to, which makes animations repeatable in tests and
+//! replays.
+use std::cell::Cell;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// The frame rate that the frame-count styles (`frame_rate`, `close_frame_rate`, ...) were tuned
+/// for. Counts are converted to time at this rate.
+pub const NOMINAL_FPS: f64 = 60.0;
+
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// Lets widget builders that hold a `&Clock` keep deriving `Debug`.
+impl<'a> fmt::Debug for Clock + 'a {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Clock({:?})", self.now())
+    }
+}
+
+/// The real time, from `Instant::now`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// The clock widgets use when none is given.
+pub static SYSTEM_CLOCK: SystemClock = SystemClock;
+
+/// A clock that stands still until it is advanced.
+#[derive(Clone, Debug)]
+pub struct ManualClock {
+    base: Instant,
+    offset: Cell<Duration>,
+}
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock {
+            base: Instant::now(),
+            offset: Cell::new(Duration::new(0, 0)),
+        }
+    }
+    /// Move the clock forward by `d`.
+    pub fn advance(&self, d: Duration) {
+        self.offset.set(self.offset.get() + d);
+    }
+    /// Move the clock forward by `n` frames at `NOMINAL_FPS`.
+    pub fn advance_frames(&self, n: u32) {
+        self.advance(from_secs(n as f64 / NOMINAL_FPS));
+    }
+    /// Set how far the clock is from where it started.
+    pub fn set(&self, elapsed: Duration) {
+        self.offset.set(elapsed);
+    }
+    /// How far the clock has been advanced since it was created.
+    pub fn elapsed(&self) -> Duration {
+        self.offset.get()
+    }
+}
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new()
+    }
+}
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.base + self.offset.get()
+    }
+}
+
+/// The time from `start` to now, or zero if `start` is in the clock's future.
+pub fn since(clock: &Clock, start: Instant) -> Duration {
+    let now = clock.now();
+    if now > start {
+        now.duration_since(start)
+    } else {
+        Duration::new(0, 0)
+    }
+}
+
+/// The number of `NOMINAL_FPS` frames since `start`.
+pub fn frames_since(clock: &Clock, start: Instant) -> f64 {
+    secs(since(clock, start)) * NOMINAL_FPS
+}
+
+pub fn secs(d: Duration) -> f64 {
+    d.as_secs() as f64 + d.subsec_nanos() as f64 * 1e-9
+}
+
+pub fn from_secs(s: f64) -> Duration {
+    let s = s.max(0.0);
+    Duration::new(s.trunc() as u64, (s.fract() * 1e9) as u32)
+}
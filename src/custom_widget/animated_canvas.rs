@@ -8,6 +8,10 @@ use conrod_core::text;
 use conrod_core::widget;
 use conrod_core::widget::primitive::image::Image;
 use custom_widget::image_hover::{ImageHover, Hoverable};
+use custom_widget::nine_slice::{NineSlice, NineSliceImage};
+use clock::{frames_since, Clock, SYSTEM_CLOCK};
+use tween::Easing;
+use std::time::Instant;
 pub struct ImageHoverStruct(Image, Option<Image>, Option<Image>);
 impl Hoverable for ImageHoverStruct {
     fn idle(&self) -> Image {
@@ -51,12 +55,16 @@ pub struct Canvas<'a> {
     pub close_icon: Option<image::Id>,
     /// close icon image source rectange
     pub close_icon_src_rect: Option<Rect>,
+    /// nine-slice image drawn in place of the rectangle surface
+    pub background: Option<NineSliceImage>,
+    /// time source for the open and close transitions
+    pub clock: &'a Clock,
 }
 
 /// **Canvas** state to be cached.
 pub struct State {
     ids: Ids,
-    frame: f64,
+    start: Instant,
     closing: bool,
 }
 
@@ -65,7 +73,8 @@ widget_ids! {
         rectangle,
         title_bar,
         close_icon_background,
-        close_icon
+        close_icon,
+        background,
     }
 }
 
@@ -116,9 +125,12 @@ pub struct Style {
     /// The label's typographic alignment over the *x* axis.
     #[conrod(default = "text::Justify::Center")]
     pub title_bar_justify: Option<text::Justify>,
-    /// The number of frames to finish transition
+    /// The number of frames, at `clock::NOMINAL_FPS`, to finish transition
     #[conrod(default = "60")]
     pub frame_rate: Option<usize>,
+    /// The curve followed while opening and closing
+    #[conrod(default = "Easing::Linear")]
+    pub easing: Option<Easing>,
     /// close icon dimension
     #[conrod(default="[80.0,80.0]")]
     pub close_icon_dim: Option<[f64; 2]>,
@@ -169,6 +181,8 @@ impl<'a> Canvas<'a> {
             close_icon: None,
             close_icon_src_rect: None,
             parent_id: None,
+            background: None,
+            clock: &SYSTEM_CLOCK,
         }
     }
 
@@ -180,6 +194,7 @@ impl<'a> Canvas<'a> {
         pub pad_top { style.pad_top = Some(Scalar) }
         pub with_style { style = Style }
         pub frame_rate {style.frame_rate = Some(usize)}
+        pub easing {style.easing = Some(Easing)}
         pub close_icon_dim {style.close_icon_dim = Some([f64;2])}
         pub close_icon_color {style.close_icon_color=Some(Color)}
     }
@@ -258,6 +273,16 @@ impl<'a> Canvas<'a> {
         self.close_icon_src_rect = Some(_rect);
         self
     }
+    /// Draw a nine-slice image as the Canvas' surface instead of a plain rectangle.
+    pub fn nine_slice_background(mut self, image: NineSliceImage) -> Self {
+        self.background = Some(image);
+        self
+    }
+    /// Read the time from `clock` instead of the system clock.
+    pub fn clock(mut self, clock: &'a Clock) -> Self {
+        self.clock = clock;
+        self
+    }
     /// Set an parent Id for the close icon
     pub fn parent(mut self, parentid: widget::Id) -> Self {
         self.parent_id = Some(parentid);
@@ -274,7 +299,7 @@ impl<'a> Widget for Canvas<'a> {
     fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
         State {
             ids: Ids::new(id_gen),
-            frame: 0.0,
+            start: self.clock.now(),
             closing: false,
         }
     }
@@ -325,32 +350,49 @@ impl<'a> Widget for Canvas<'a> {
     /// Update the state of the Canvas.
     fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
         let widget::UpdateArgs { id, state, rect, mut ui, .. } = args;
-        let Canvas { style, maybe_title_bar_label, maybe_splits, .. } = self;
+        let Canvas { style, maybe_title_bar_label, maybe_splits, background, .. } = self;
 
-        state.update(|state| state.frame += 1.0);
+        let frame = frames_since(self.clock, state.start);
         // BorderedRectangle widget as the rectangle backdrop.
         let dim = rect.dim();
         let color = style.color(ui.theme());
         let border = style.border(ui.theme());
         let border_color = style.border_color(ui.theme());
         let frame_rate = style.frame_rate(ui.theme()) as f64;
-        let _step = if state.frame > frame_rate {
+        let easing = style.easing(ui.theme());
+        // Closing is over once its frames are up. The step is kept within 0 and 1, as the Back
+        // and Elastic curves overshoot on the way.
+        let closed = state.closing && frame >= frame_rate;
+        let _step = if closed {
+            0.0
+        } else if frame > frame_rate {
             1.0
         } else {
-            if state.closing {
-                1.0 - state.frame / frame_rate
-            } else {
-                state.frame / frame_rate
-            }
+            let t = easing.apply(frame / frame_rate);
+            let step = if state.closing { 1.0 - t } else { t };
+            step.max(0.0).min(1.0)
+        };
+        // The rectangle is still needed by the title bar, so hide it under a nine-slice surface.
+        let (rect_color, rect_border) = match background {
+            Some(_) => (color::TRANSPARENT, 0.0),
+            None => (color, border),
         };
         widget::BorderedRectangle::new(dim)
-            .color(color)
-            .border(border)
+            .color(rect_color)
+            .border(rect_border)
             .border_color(border_color)
             .middle_of(id)
             .graphics_for(id)
             .place_on_kid_area(false)
             .set(state.ids.rectangle, &mut ui);
+        if let Some(image) = background {
+            NineSlice::new(image)
+                .wh_of(id)
+                .middle_of(id)
+                .graphics_for(id)
+                .place_on_kid_area(false)
+                .set(state.ids.background, &mut ui);
+        }
 
         // TitleBar widget if we were given some label.
         if let Some(label) = maybe_title_bar_label {
@@ -485,14 +527,15 @@ impl<'a> Widget for Canvas<'a> {
                    .middle_of(state.ids.close_icon_background)
                    .set(state.ids.close_icon, ui)
                    .was_clicked() {
+                let now = self.clock.now();
                 state.update(|state| {
-                                 state.frame = 0.0;
+                                 state.start = now;
                                  state.closing = true;
                              });
             }
         }
 
-        CanvasY(state.closing, _step)
+        CanvasY(state.closing, _step, closed)
     }
 }
 
@@ -544,13 +587,10 @@ impl<'a> Labelable<'a> for Canvas<'a> {
         label_font_size { style.title_bar_font_size = Some(FontSize) }
     }
 }
-pub struct CanvasY(bool, f64);
+/// Whether the canvas is closing, its step, and whether closing has run all its frames.
+pub struct CanvasY(bool, f64, bool);
 impl CanvasY {
     pub fn is_done(&self) -> bool {
-        if (self.0) & (self.1 == 0.0) {
-            true
-        } else {
-            false
-        }
+        self.0 & self.2
     }
 }
@@ -0,0 +1,605 @@
+//! A board split into named zones of card slots, e.g. a creatures row, a lands row and a
+//! graveyard.
+//!
+//! The app keeps the cards in a `Zones` and sets a `Board` over it. Cards are dragged between
+//! slots: while a card is held, the zones that would take it are highlighted along with the slot
+//! it would snap into. Letting go anywhere else sends it back to its slot.
+//!
+//! Cards can also be dropped in from other widgets. Give a `DragDropList` the board as its
+//! `exit_id` and a `held` to write the card being dragged to, and pass that card to
+//! `Board::external_drag` so the zones are highlighted for it too. Hand the item the list lets
+//! go of to `Zones::drop_card` with the mouse position. The list has already taken the card out
+//! by then, so a card the board hands back has to be put back into the list's values:
+//!
+//! ```ignore
+//! let mut held = None;
+//! let exited = DragDropList::new(&mut hand, hand_closure, 100.0)
+//!     .exit_id(Some(Some(ids.board)))
+//!     .held(&mut held)
+//!     .set(ids.hand, ui);
+//! let board = Board::new(&mut zones, board_closure);
+//! let board = match held {
+//!     Some(ref card) => board.external_drag(card),
+//!     None => board,
+//! };
+//! board.set(ids.board, ui);
+//! if let Some(card) = exited {
+//!     let xy = ui.global_input().current.mouse.xy;
+//!     if let Err(card) = zones.drop_card(card, xy) {
+//!         hand.push(card);
+//!     }
+//! }
+//! ```
+use conrod_core::{widget, color, text, Color, Colorable, FontSize, Point, Positionable, Rect,
+                  Scalar, Sizeable, Widget};
+use clock::{since, Clock, SYSTEM_CLOCK};
+use tween::{Easing, Tween};
+use std::time::{Duration, Instant};
+
+/// A slot on the board: the index of its zone and of the slot within the zone.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SlotId {
+    pub zone: usize,
+    pub slot: usize,
+}
+
+/// A named row of slots that each hold at most one card.
+pub struct Zone<T> {
+    pub name: String,
+    /// The cards in the zone by slot. The zone's capacity is the number of slots.
+    pub slots: Vec<Option<T>>,
+    accept: Box<Fn(&T) -> bool>,
+    /// The zone's share of the board's height, against the weights of the other zones.
+    pub weight: f64,
+    /// Where the zone and its slots were last drawn.
+    rect: Option<Rect>,
+    slot_rects: Vec<Rect>,
+}
+
+impl<T> Zone<T> {
+    /// An empty zone of `capacity` slots that takes any card.
+    pub fn new(name: &str, capacity: usize) -> Self {
+        Zone {
+            name: name.to_owned(),
+            slots: (0..capacity).map(|_| None).collect(),
+            accept: Box::new(|_| true),
+            weight: 1.0,
+            rect: None,
+            slot_rects: vec![],
+        }
+    }
+    /// Only take the cards `accept` returns `true` for.
+    pub fn accept(mut self, accept: Box<Fn(&T) -> bool>) -> Self {
+        self.accept = accept;
+        self
+    }
+    pub fn weight(mut self, weight: f64) -> Self {
+        self.weight = weight;
+        self
+    }
+    pub fn accepts(&self, card: &T) -> bool {
+        (*self.accept)(card)
+    }
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+    /// The number of cards in the zone.
+    pub fn len(&self) -> usize {
+        self.slots.iter().filter(|s| s.is_some()).count()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn is_full(&self) -> bool {
+        self.free_slot().is_none()
+    }
+    /// The first empty slot.
+    pub fn free_slot(&self) -> Option<usize> {
+        self.slots.iter().position(|s| s.is_none())
+    }
+    /// Put `card` into the first empty slot, whether the zone accepts it or not. Hands the card
+    /// back if the zone is full.
+    pub fn put(&mut self, card: T) -> Result<usize, T> {
+        match self.free_slot() {
+            Some(slot) => {
+                self.slots[slot] = Some(card);
+                Ok(slot)
+            }
+            None => Err(card),
+        }
+    }
+    pub fn take(&mut self, slot: usize) -> Option<T> {
+        self.slots.get_mut(slot).and_then(|s| s.take())
+    }
+    /// Where the zone was last drawn.
+    pub fn rect(&self) -> Option<Rect> {
+        self.rect
+    }
+    /// Where slot `slot` was last drawn.
+    pub fn slot_rect(&self, slot: usize) -> Option<Rect> {
+        self.slot_rects.get(slot).cloned()
+    }
+}
+
+/// The zones of a board, from top to bottom.
+pub struct Zones<T> {
+    pub zones: Vec<Zone<T>>,
+}
+
+impl<T> Default for Zones<T> {
+    fn default() -> Self {
+        Zones { zones: vec![] }
+    }
+}
+
+impl<T> Zones<T> {
+    pub fn new() -> Self {
+        Zones::default()
+    }
+    /// Add `zone` below the others and return its index.
+    pub fn add(&mut self, zone: Zone<T>) -> usize {
+        self.zones.push(zone);
+        self.zones.len() - 1
+    }
+    /// The index of the zone called `name`.
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.zones.iter().position(|z| z.name == name)
+    }
+    pub fn get(&self, name: &str) -> Option<&Zone<T>> {
+        self.zones.iter().find(|z| z.name == name)
+    }
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut Zone<T>> {
+        self.zones.iter_mut().find(|z| z.name == name)
+    }
+    pub fn card(&self, at: SlotId) -> Option<&T> {
+        self.zones.get(at.zone).and_then(|z| z.slots.get(at.slot)).and_then(|s| s.as_ref())
+    }
+    /// Whether `card` may go into the slot `at`: the slot is empty and its zone accepts it.
+    pub fn can_place(&self, card: &T, at: SlotId) -> bool {
+        match self.zones.get(at.zone) {
+            Some(zone) => {
+                zone.accepts(card) && zone.slots.get(at.slot).map_or(false, |s| s.is_none())
+            }
+            None => false,
+        }
+    }
+    /// Move the card in `from` to `to` if it may go there. Returns whether it moved.
+    pub fn move_card(&mut self, from: SlotId, to: SlotId) -> bool {
+        if from == to || !self.card(from).map_or(false, |c| self.can_place(c, to)) {
+            return false;
+        }
+        let card = self.zones[from.zone].take(from.slot);
+        self.zones[to.zone].slots[to.slot] = card;
+        true
+    }
+    /// The zone last drawn under `xy`.
+    pub fn zone_at(&self, xy: Point) -> Option<usize> {
+        self.zones.iter().position(|z| z.rect.map_or(false, |r| r.is_over(xy)))
+    }
+    /// The slot last drawn under `xy`.
+    pub fn slot_at(&self, xy: Point) -> Option<SlotId> {
+        let zone = match self.zone_at(xy) {
+            Some(zone) => zone,
+            None => return None,
+        };
+        self.zones[zone].slot_rects.iter().position(|r| r.is_over(xy)).map(|slot| {
+            SlotId {
+                zone: zone,
+                slot: slot,
+            }
+        })
+    }
+    /// The slot `card` snaps into when let go at `xy`: the slot under `xy` if it is empty,
+    /// otherwise the empty slot of the same zone nearest to `xy`. `None` if there is no zone
+    /// under `xy`, it doesn't accept the card or it is full.
+    pub fn target(&self, card: &T, xy: Point) -> Option<SlotId> {
+        let zone_i = match self.zone_at(xy) {
+            Some(zone) => zone,
+            None => return None,
+        };
+        let zone = &self.zones[zone_i];
+        if !zone.accepts(card) {
+            return None;
+        }
+        let distance = |r: &Rect| (r.x() - xy[0]).powi(2) + (r.y() - xy[1]).powi(2);
+        zone.slot_rects
+            .iter()
+            .enumerate()
+            .filter(|&(slot, _)| zone.slots.get(slot).map_or(false, |s| s.is_none()))
+            .min_by(|a, b| distance(a.1).partial_cmp(&distance(b.1)).unwrap())
+            .map(|(slot, _)| {
+                SlotId {
+                    zone: zone_i,
+                    slot: slot,
+                }
+            })
+    }
+    /// Put `card`, dragged in from elsewhere, into the slot it snaps into at `xy`. Hands the
+    /// card back if there is none.
+    pub fn drop_card(&mut self, card: T, xy: Point) -> Result<SlotId, T> {
+        match self.target(&card, xy) {
+            Some(at) => {
+                self.zones[at.zone].slots[at.slot] = Some(card);
+                Ok(at)
+            }
+            None => Err(card),
+        }
+    }
+}
+
+/// What happened to a dragged card.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BoardEvent {
+    /// The card snapped into another slot.
+    Moved { from: SlotId, to: SlotId },
+    /// The card was let go where it can't go and is on its way back.
+    Rejected(SlotId),
+}
+
+/// Draws a `Zones` as rows filling the widget, each row as high as its zone's weight allows,
+/// and lets the cards be dragged from slot to slot.
+#[derive(WidgetCommon)]
+pub struct Board<'a, T, W>
+    where T: Clone + 'a,
+          W: Widget
+{
+    /// An object that handles some of the dirty work of rendering a GUI. We don't
+    /// really have to worry about it.
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    pub zones: &'a mut Zones<T>,
+    widget_closure: Box<Fn(T) -> W>,
+    pub clock: &'a Clock,
+    /// A card being dragged over the board by another widget.
+    pub external: Option<&'a T>,
+    /// See the Style struct below.
+    style: Style,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
+pub struct Style {
+    /// The largest a card is drawn. Cards shrink to fit the slots into their zone.
+    #[conrod(default = "[100.0, 140.0]")]
+    pub card_dim: Option<[f64; 2]>,
+    /// The gap between slots and around the edges of a zone.
+    #[conrod(default = "6.0")]
+    pub spacing: Option<Scalar>,
+    /// Color of the zones.
+    #[conrod(default = "theme.background_color")]
+    pub color: Option<Color>,
+    /// Color of the zones that would take the card being dragged.
+    #[conrod(default = "color::LIGHT_GREEN.alpha(0.4)")]
+    pub highlight_color: Option<Color>,
+    /// Color of the outline of an empty slot.
+    #[conrod(default = "theme.border_color")]
+    pub slot_color: Option<Color>,
+    /// Color of the outline of the slot the dragged card would snap into.
+    #[conrod(default = "color::YELLOW")]
+    pub target_color: Option<Color>,
+    /// How long a card let go in the wrong place takes to get back to its slot.
+    #[conrod(default = "Duration::from_millis(250)")]
+    pub return_duration: Option<Duration>,
+    #[conrod(default = "theme.label_color")]
+    pub label_color: Option<Color>,
+    #[conrod(default = "theme.font_size_small")]
+    pub label_font_size: Option<FontSize>,
+    /// Specify a unique font for the zone names.
+    #[conrod(default = "theme.font_id")]
+    pub label_font_id: Option<Option<text::font::Id>>,
+}
+
+widget_ids! {
+    struct Ids {
+        zones[],
+        labels[],
+    }
+}
+
+/// Represents the unique, cached state for our Board widget.
+pub struct State {
+    ids: Ids,
+    /// The slot outline ids of each zone, so a zone growing doesn't move the ones after it.
+    slots: Vec<widget::id::List>,
+    /// The card ids of each zone, by slot.
+    cards: Vec<widget::id::List>,
+    /// The slot of the card being dragged.
+    dragging: Option<SlotId>,
+    /// The slot a rejected card is going back to, where it was let go and when.
+    returning: Option<(SlotId, Point, Instant)>,
+}
+
+impl<'a, T, W> Board<'a, T, W>
+    where T: Clone + 'a,
+          W: Widget
+{
+    /// Create a button context to be built upon.
+    pub fn new(zones: &'a mut Zones<T>, widget_closure: Box<Fn(T) -> W>) -> Self {
+        Board {
+            zones: zones,
+            widget_closure: widget_closure,
+            clock: &SYSTEM_CLOCK,
+            external: None,
+            common: widget::CommonBuilder::default(),
+            style: Style::default(),
+        }
+    }
+    /// Read the time from `clock` instead of the system clock.
+    pub fn clock(mut self, clock: &'a Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+    /// Highlight the zones and the slot for `card`, held by another widget, as if it were
+    /// dragged from the board. Dropping it is left to `Zones::drop_card`.
+    pub fn external_drag(mut self, card: &'a T) -> Self {
+        self.external = Some(card);
+        self
+    }
+    /// Specify the font used for the zone names.
+    pub fn label_font_id(mut self, font_id: text::font::Id) -> Self {
+        self.style.label_font_id = Some(Some(font_id));
+        self
+    }
+    builder_methods!{
+        pub card_dim { style.card_dim = Some([f64; 2]) }
+        pub spacing { style.spacing = Some(Scalar) }
+        pub highlight_color { style.highlight_color = Some(Color) }
+        pub slot_color { style.slot_color = Some(Color) }
+        pub target_color { style.target_color = Some(Color) }
+        pub return_duration { style.return_duration = Some(Duration) }
+        pub label_color { style.label_color = Some(Color) }
+        pub label_font_size { style.label_font_size = Some(FontSize) }
+    }
+}
+
+/// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
+/// documentation for more details.
+impl<'a, T, W> Widget for Board<'a, T, W>
+    where T: Clone + 'a,
+          W: Widget
+{
+    /// The State struct that we defined above.
+    type State = State;
+    /// The Style struct that we defined using the `widget_style!` macro.
+    type Style = Style;
+    /// The event produced by instantiating the widget.
+    type Event = Vec<BoardEvent>;
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State {
+            ids: Ids::new(id_gen),
+            slots: vec![],
+            cards: vec![],
+            dragging: None,
+            returning: None,
+        }
+    }
+
+    fn style(&self) -> Self::Style {
+        self.style.clone()
+    }
+
+    /// Update the state of the button by handling any input that has occurred since the last
+    /// update.
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { id, state, rect, ui, .. } = args;
+        let zones = self.zones;
+        let card_dim = self.style.card_dim(&ui.theme);
+        let spacing = self.style.spacing(&ui.theme);
+        let font_size = self.style.label_font_size(&ui.theme);
+        let now = self.clock.now();
+        let mut events = vec![];
+
+        if state.ids.zones.len() < zones.zones.len() {
+            let id_gen = &mut ui.widget_id_generator();
+            state.update(|state| {
+                state.ids.zones.resize(zones.zones.len(), id_gen);
+                state.ids.labels.resize(zones.zones.len(), id_gen);
+            });
+        }
+        let grown = state.slots.len() < zones.zones.len() ||
+                    zones.zones
+                        .iter()
+                        .zip(state.slots.iter())
+                        .any(|(zone, slots)| slots.len() < zone.capacity());
+        if grown {
+            let id_gen = &mut ui.widget_id_generator();
+            state.update(|state| {
+                let n = state.slots.len().max(zones.zones.len());
+                state.slots.resize(n, widget::id::List::new());
+                state.cards.resize(n, widget::id::List::new());
+                for (zone_i, zone) in zones.zones.iter().enumerate() {
+                    if state.slots[zone_i].len() < zone.capacity() {
+                        state.slots[zone_i].resize(zone.capacity(), id_gen);
+                        state.cards[zone_i].resize(zone.capacity(), id_gen);
+                    }
+                }
+            });
+        }
+
+        // Lay the zones out as rows, top to bottom, leaving room for the names above the slots.
+        let label_h = font_size as Scalar * 1.5;
+        let total_weight = zones.zones.iter().map(|z| z.weight.max(0.0)).sum::<f64>();
+        let mut top = rect.top();
+        for zone in zones.zones.iter_mut() {
+            let h = if total_weight > 0.0 {
+                rect.h() * zone.weight.max(0.0) / total_weight
+            } else {
+                0.0
+            };
+            let zone_rect = Rect::from_corners([rect.left(), top - h], [rect.right(), top]);
+            top -= h;
+            let slot_area = Rect::from_corners([zone_rect.left(), zone_rect.bottom()],
+                                               [zone_rect.right(), zone_rect.top() - label_h]);
+            zone.rect = Some(zone_rect);
+            zone.slot_rects = slot_rects(slot_area, zone.capacity(), card_dim, spacing);
+        }
+
+        // Pick a card up, or let it go.
+        let mouse_xy = ui.global_input().current.mouse.xy;
+        let released = ui.global_input().current.mouse.buttons.left().is_up();
+        let mut dragging = state.dragging.filter(|&at| zones.card(at).is_some());
+        let return_duration = self.style.return_duration(&ui.theme);
+        let mut returning = state.returning.filter(|&(at, _, start)| {
+            zones.card(at).is_some() && since(self.clock, start) < return_duration
+        });
+        if dragging.is_none() {
+            'zones: for (zone_i, zone) in zones.zones.iter().enumerate() {
+                for slot in 0..zone.capacity() {
+                    let at = SlotId {
+                        zone: zone_i,
+                        slot: slot,
+                    };
+                    let card_id = state.cards[zone_i][slot];
+                    if zone.slots[slot].is_none() || returning.map_or(false, |r| r.0 == at) {
+                        continue;
+                    }
+                    if ui.widget_input(card_id).drags().left().next().is_some() {
+                        dragging = Some(at);
+                        break 'zones;
+                    }
+                }
+            }
+        }
+        let mut target = None;
+        if let Some(from) = dragging {
+            target = match zones.slot_at(mouse_xy) {
+                Some(at) if at == from => Some(from),
+                _ => zones.card(from).and_then(|card| zones.target(card, mouse_xy)),
+            };
+            if released {
+                dragging = None;
+                match target {
+                    Some(to) if to == from => {}
+                    Some(to) => {
+                        zones.move_card(from, to);
+                        events.push(BoardEvent::Moved {
+                                        from: from,
+                                        to: to,
+                                    });
+                    }
+                    None => {
+                        returning = Some((from, mouse_xy, now));
+                        events.push(BoardEvent::Rejected(from));
+                    }
+                }
+                target = None;
+            }
+        } else if let Some(card) = self.external {
+            target = zones.target(card, mouse_xy);
+        }
+
+        // The zones, their slots and the cards resting in them.
+        let held = dragging.and_then(|at| zones.card(at).cloned())
+            .or_else(|| self.external.cloned());
+        let label_color = self.style.label_color(&ui.theme);
+        let font_id = self.style.label_font_id(&ui.theme);
+        let mut held_dim = card_dim;
+        let mut returning_to = None;
+        for (zone_i, zone) in zones.zones.iter().enumerate() {
+            let zone_rect = zone.rect.unwrap_or(rect);
+            let takes_held = held.as_ref()
+                .map_or(false, |card| zone.accepts(card) && !zone.is_full());
+            let zone_color = if takes_held {
+                self.style.highlight_color(&ui.theme)
+            } else {
+                self.style.color(&ui.theme)
+            };
+            widget::Rectangle::fill_with(zone_rect.dim(), zone_color)
+                .xy(zone_rect.xy())
+                .parent(id)
+                .graphics_for(id)
+                .set(state.ids.zones[zone_i], ui);
+            widget::Text::new(&zone.name)
+                .font_size(font_size)
+                .color(label_color)
+                .and_then(font_id, widget::Text::font_id)
+                .top_left_with_margins_on(state.ids.zones[zone_i], label_h * 0.2, spacing)
+                .parent(id)
+                .graphics_for(id)
+                .set(state.ids.labels[zone_i], ui);
+            for (slot, slot_rect) in zone.slot_rects.iter().enumerate() {
+                let at = SlotId {
+                    zone: zone_i,
+                    slot: slot,
+                };
+                let outline = if target == Some(at) {
+                    self.style.target_color(&ui.theme)
+                } else {
+                    self.style.slot_color(&ui.theme)
+                };
+                widget::Rectangle::outline_styled(slot_rect.dim(),
+                                                  widget::line::Style::solid().color(outline))
+                    .xy(slot_rect.xy())
+                    .parent(id)
+                    .graphics_for(id)
+                    .set(state.slots[zone_i][slot], ui);
+                if dragging == Some(at) {
+                    held_dim = slot_rect.dim();
+                    continue;
+                }
+                if returning.map_or(false, |r| r.0 == at) {
+                    returning_to = Some((at, *slot_rect));
+                    continue;
+                }
+                if let Some(ref card) = zone.slots[slot] {
+                    (*self.widget_closure)(card.clone())
+                        .wh(slot_rect.dim())
+                        .xy(slot_rect.xy())
+                        .parent(id)
+                        .set(state.cards[zone_i][slot], ui);
+                }
+            }
+        }
+
+        // The held and returning cards go on top of everything else.
+        if let (Some(at), Some(card)) = (dragging, held) {
+            (*self.widget_closure)(card)
+                .wh(held_dim)
+                .xy(mouse_xy)
+                .parent(id)
+                .set(state.cards[at.zone][at.slot], ui);
+        }
+        if let (Some((_, from, start)), Some((at, slot_rect))) = (returning, returning_to) {
+            let back = Tween::new(from, slot_rect.xy(), return_duration).easing(Easing::CubicOut);
+            if let Some(card) = zones.card(at).cloned() {
+                (*self.widget_closure)(card)
+                    .wh(slot_rect.dim())
+                    .xy(back.value_at(since(self.clock, start)))
+                    .parent(id)
+                    .set(state.cards[at.zone][at.slot], ui);
+            }
+        }
+
+        if dragging != state.dragging || returning != state.returning {
+            state.update(|state| {
+                state.dragging = dragging;
+                state.returning = returning;
+            });
+        }
+        events
+    }
+}
+
+impl<'a, T, W> Colorable for Board<'a, T, W>
+    where T: Clone + 'a,
+          W: Widget
+{
+    builder_method!(color { style.color = Some(Color) });
+}
+
+/// The rects of `n` slots side by side in the middle of `area`, each as big as `card_dim`
+/// allows while keeping its shape and fitting them all in.
+fn slot_rects(area: Rect, n: usize, card_dim: [f64; 2], spacing: Scalar) -> Vec<Rect> {
+    if n == 0 {
+        return vec![];
+    }
+    let fit_w = ((area.w() - spacing * (n + 1) as Scalar) / n as Scalar).max(0.0);
+    let fit_h = (area.h() - spacing * 2.0).max(0.0);
+    let scale = (fit_w / card_dim[0]).min(fit_h / card_dim[1]).min(1.0);
+    let dim = [card_dim[0] * scale, card_dim[1] * scale];
+    let row_w = dim[0] * n as Scalar + spacing * (n - 1) as Scalar;
+    let left = area.x() - row_w / 2.0 + dim[0] / 2.0;
+    (0..n)
+        .map(|i| Rect::from_xy_dim([left + (dim[0] + spacing) * i as Scalar, area.y()], dim))
+        .collect()
+}
@@ -0,0 +1,423 @@
+use conrod_core::{widget, color, image, text, Color, Colorable, FontSize, Labelable, Positionable,
+                  Rect, Scalar, Sizeable, Widget};
+use custom_widget::bordered_image::BorderedImage;
+use custom_widget::image_panels::list_select;
+use text::{font_of, text_width};
+use std::cmp::Ordering;
+
+/// A card as the `CardCollection` sorts, filters and draws it.
+pub trait Collectable {
+    fn name(&self) -> &str;
+    fn cost(&self) -> u32;
+    /// E.g. "Creature - Goblin".
+    fn card_type(&self) -> &str;
+    /// The image drawn in the grid, and the part of it to draw if it is a sprite sheet.
+    fn image(&self) -> (image::Id, Option<Rect>);
+    /// Extra words that filter chips and the search match, e.g. colours or keywords.
+    fn tags(&self) -> Vec<&str> {
+        vec![]
+    }
+}
+
+/// What the collection is sorted by. Cards that tie are sorted by name.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SortKey {
+    Name,
+    Cost,
+    Type,
+}
+impl SortKey {
+    pub fn label(&self) -> &'static str {
+        match *self {
+            SortKey::Name => "Name",
+            SortKey::Cost => "Cost",
+            SortKey::Type => "Type",
+        }
+    }
+}
+
+/// Something that happened to a card of the collection, by its index in the cards.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CollectionEvent {
+    Click(usize),
+    /// The card is being dragged. Reported on every update in which the mouse moves.
+    Drag(usize),
+}
+
+/// The search, sort and filter chips of a `CardCollection`, kept by the app so they outlive the
+/// widget and can be set from code.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollectionQuery {
+    /// Words that must all appear in a card's name, type or tags, regardless of case.
+    pub search: String,
+    pub sort: SortKey,
+    pub descending: bool,
+    /// The chips switched on. A card must match at least one of them.
+    pub chips: Vec<String>,
+}
+
+impl Default for CollectionQuery {
+    fn default() -> Self {
+        CollectionQuery {
+            search: String::new(),
+            sort: SortKey::Name,
+            descending: false,
+            chips: vec![],
+        }
+    }
+}
+
+impl CollectionQuery {
+    pub fn new() -> Self {
+        CollectionQuery::default()
+    }
+    /// Sort by `key`, or reverse the order if the collection is already sorted by it.
+    pub fn sort_by(&mut self, key: SortKey) {
+        if self.sort == key {
+            self.descending = !self.descending;
+        } else {
+            self.sort = key;
+            self.descending = false;
+        }
+    }
+    /// Switch the chip `chip` on or off.
+    pub fn toggle_chip(&mut self, chip: &str) {
+        match self.chips.iter().position(|c| c == chip) {
+            Some(i) => {
+                self.chips.remove(i);
+            }
+            None => self.chips.push(chip.to_owned()),
+        }
+    }
+    pub fn is_chip_on(&self, chip: &str) -> bool {
+        self.chips.iter().any(|c| c == chip)
+    }
+    /// Whether `card` passes the search and chips. A chip matches a word of the card's type or
+    /// one of its tags.
+    pub fn matches<C: Collectable>(&self, card: &C) -> bool {
+        let tags = card.tags();
+        let chip_matches = |chip: &String| {
+            card.card_type().split_whitespace().any(|w| w.eq_ignore_ascii_case(chip)) ||
+            tags.iter().any(|t| t.eq_ignore_ascii_case(chip))
+        };
+        if !self.chips.is_empty() && !self.chips.iter().any(chip_matches) {
+            return false;
+        }
+        let haystack = format!("{} {} {}", card.name(), card.card_type(), tags.join(" "))
+            .to_lowercase();
+        self.search.to_lowercase().split_whitespace().all(|w| haystack.contains(w))
+    }
+    /// The indices of the cards that pass, in sorted order.
+    pub fn apply<C: Collectable>(&self, cards: &[C]) -> Vec<usize> {
+        let mut shown: Vec<usize> = (0..cards.len())
+            .filter(|&i| self.matches(&cards[i]))
+            .collect();
+        let by_name = |a: &C, b: &C| a.name().to_lowercase().cmp(&b.name().to_lowercase());
+        shown.sort_by(|&a, &b| {
+            let (a, b) = (&cards[a], &cards[b]);
+            let key = match self.sort {
+                SortKey::Name => Ordering::Equal,
+                SortKey::Cost => a.cost().cmp(&b.cost()),
+                SortKey::Type => a.card_type().to_lowercase().cmp(&b.card_type().to_lowercase()),
+            };
+            let order = key.then_with(|| by_name(a, b));
+            if self.descending { order.reverse() } else { order }
+        });
+        shown
+    }
+}
+
+/// A scrollable grid for browsing a large collection, with a search box, sort buttons and
+/// filter chips above it and a badge on each card with how many copies are owned.
+///
+/// Only the rows in view are instantiated, so the grid stays cheap with hundreds of cards.
+#[derive(WidgetCommon)]
+pub struct CardCollection<'a, C: Collectable + 'a> {
+    /// An object that handles some of the dirty work of rendering a GUI. We don't
+    /// really have to worry about it.
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    cards: &'a [C],
+    pub query: &'a mut CollectionQuery,
+    /// The filter chips offered, e.g. card types or colours.
+    pub chips: &'a [&'a str],
+    /// How many copies of each card are owned, by index in the cards.
+    pub owned: Option<&'a [usize]>,
+    /// See the Style struct below.
+    style: Style,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
+pub struct Style {
+    #[conrod(default = "[100.0, 140.0]")]
+    pub card_dim: Option<[f64; 2]>,
+    /// The gap between cards.
+    #[conrod(default = "6.0")]
+    pub spacing: Option<Scalar>,
+    /// The height of the search bar and of the row of chips.
+    #[conrod(default = "30.0")]
+    pub bar_h: Option<Scalar>,
+    /// Color of the buttons and chips.
+    #[conrod(default = "theme.shape_color")]
+    pub color: Option<Color>,
+    /// Color of the sort button in use and the chips switched on.
+    #[conrod(default = "color::LIGHT_BLUE")]
+    pub selected_color: Option<Color>,
+    #[conrod(default = "theme.shape_color")]
+    pub badge_color: Option<Color>,
+    #[conrod(default = "theme.label_color")]
+    pub label_color: Option<Color>,
+    #[conrod(default = "theme.font_size_small")]
+    pub label_font_size: Option<FontSize>,
+    /// Specify a unique font for the labels.
+    #[conrod(default = "theme.font_id")]
+    pub label_font_id: Option<Option<text::font::Id>>,
+}
+
+widget_ids! {
+    struct Ids {
+        search,
+        sort_buttons[],
+        chips[],
+        grid,
+        faces[],
+        badges[],
+        counts[],
+    }
+}
+
+/// Represents the unique, cached state for our CardCollection widget.
+pub struct State {
+    ids: Ids,
+}
+
+const SORT_KEYS: [SortKey; 3] = [SortKey::Name, SortKey::Cost, SortKey::Type];
+
+impl<'a, C: Collectable + 'a> CardCollection<'a, C> {
+    /// Create a button context to be built upon.
+    pub fn new(cards: &'a [C], query: &'a mut CollectionQuery) -> Self {
+        CardCollection {
+            cards: cards,
+            query: query,
+            chips: &[],
+            owned: None,
+            common: widget::CommonBuilder::default(),
+            style: Style::default(),
+        }
+    }
+    /// Offer a chip for each of `chips` under the search bar.
+    pub fn chips(mut self, chips: &'a [&'a str]) -> Self {
+        self.chips = chips;
+        self
+    }
+    /// Show `owned[i]` in a badge on card `i`.
+    pub fn owned(mut self, owned: &'a [usize]) -> Self {
+        self.owned = Some(owned);
+        self
+    }
+    /// Specify the font used for the labels.
+    pub fn label_font_id(mut self, font_id: text::font::Id) -> Self {
+        self.style.label_font_id = Some(Some(font_id));
+        self
+    }
+    builder_methods!{
+        pub card_dim { style.card_dim = Some([f64; 2]) }
+        pub spacing { style.spacing = Some(Scalar) }
+        pub bar_h { style.bar_h = Some(Scalar) }
+        pub selected_color { style.selected_color = Some(Color) }
+        pub badge_color { style.badge_color = Some(Color) }
+        pub label_color { style.label_color = Some(Color) }
+        pub label_font_size { style.label_font_size = Some(FontSize) }
+    }
+}
+
+/// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
+/// documentation for more details.
+impl<'a, C: Collectable + 'a> Widget for CardCollection<'a, C> {
+    /// The State struct that we defined above.
+    type State = State;
+    /// The Style struct that we defined using the `widget_style!` macro.
+    type Style = Style;
+    /// The event produced by instantiating the widget.
+    type Event = Vec<CollectionEvent>;
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State { ids: Ids::new(id_gen) }
+    }
+
+    fn style(&self) -> Self::Style {
+        self.style.clone()
+    }
+
+    /// Update the state of the button by handling any input that has occurred since the last
+    /// update.
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { id, state, rect, ui, .. } = args;
+        let query = self.query;
+        let bar_h = self.style.bar_h(&ui.theme);
+        let color = self.style.color(&ui.theme);
+        let selected_color = self.style.selected_color(&ui.theme);
+        let label_color = self.style.label_color(&ui.theme);
+        let font_size = self.style.label_font_size(&ui.theme);
+        let font_id = self.style.label_font_id(&ui.theme);
+        if state.ids.sort_buttons.len() < SORT_KEYS.len() ||
+           state.ids.chips.len() < self.chips.len() {
+            let id_gen = &mut ui.widget_id_generator();
+            let chips = self.chips.len();
+            state.update(|state| {
+                             state.ids.sort_buttons.resize(SORT_KEYS.len(), id_gen);
+                             let len = state.ids.chips.len().max(chips);
+                             state.ids.chips.resize(len, id_gen);
+                         });
+        }
+
+        // The search box takes what the sort buttons leave of the top bar.
+        let button_w = bar_h * 2.5;
+        let search_w = (rect.w() - SORT_KEYS.len() as Scalar * button_w).max(button_w);
+        let edits = widget::TextBox::new(&query.search)
+            .w_h(search_w, bar_h)
+            .font_size(font_size)
+            .text_color(label_color)
+            .and_then(font_id, widget::TextBox::font_id)
+            .top_left_of(id)
+            .parent(id)
+            .set(state.ids.search, ui);
+        for edit in edits {
+            if let widget::text_box::Event::Update(search) = edit {
+                query.search = search;
+            }
+        }
+        for (i, &key) in SORT_KEYS.iter().enumerate() {
+            let label = if query.sort == key && query.descending {
+                format!("{} v", key.label())
+            } else {
+                key.label().to_owned()
+            };
+            let button = widget::Button::new()
+                .label(&label)
+                .label_color(label_color)
+                .label_font_size(font_size)
+                .color(if query.sort == key { selected_color } else { color })
+                .w_h(button_w, bar_h)
+                .top_left_with_margins_on(id, 0.0, search_w + i as Scalar * button_w)
+                .parent(id);
+            let button = match font_id {
+                Some(font_id) => button.label_font_id(font_id),
+                None => button,
+            };
+            if button.set(state.ids.sort_buttons[i], ui).was_clicked() {
+                query.sort_by(key);
+            }
+        }
+
+        // Chips are as wide as their labels and fill the second row from the left.
+        let spacing = self.style.spacing(&ui.theme);
+        let mut chip_x = 0.0;
+        for (i, &chip) in self.chips.iter().enumerate() {
+            let chip_w = font_of(ui, font_id).map_or(bar_h * 2.5, |font| {
+                text_width(font, font_size, chip) + bar_h
+            });
+            let button = widget::Button::new()
+                .label(chip)
+                .label_color(label_color)
+                .label_font_size(font_size)
+                .color(if query.is_chip_on(chip) { selected_color } else { color })
+                .w_h(chip_w, bar_h * 0.8)
+                .top_left_with_margins_on(id, bar_h * 1.1, chip_x)
+                .parent(id);
+            let button = match font_id {
+                Some(font_id) => button.label_font_id(font_id),
+                None => button,
+            };
+            if button.set(state.ids.chips[i], ui).was_clicked() {
+                query.toggle_chip(chip);
+            }
+            chip_x += chip_w + spacing;
+        }
+
+        let shown = query.apply(self.cards);
+        let grid_top = if self.chips.is_empty() { bar_h } else { bar_h * 2.0 } + spacing;
+        let grid_dim = [rect.w(), (rect.h() - grid_top).max(0.0)];
+        let card_dim = self.style.card_dim(&ui.theme);
+        let cols = (((grid_dim[0] + spacing) / (card_dim[0] + spacing)).floor() as usize).max(1);
+        let rows = (shown.len() + cols - 1) / cols;
+        // Ids go by card rather than by place in the grid, so a card keeps its widget state
+        // when the search or sort moves it.
+        if state.ids.faces.len() < self.cards.len() {
+            let id_gen = &mut ui.widget_id_generator();
+            let len = self.cards.len();
+            state.update(|state| {
+                             state.ids.faces.resize(len, id_gen);
+                             state.ids.badges.resize(len, id_gen);
+                             state.ids.counts.resize(len, id_gen);
+                         });
+        }
+        let (mut events, scrollbar) = list_select::ListSelect::single(rows)
+            .flow_down()
+            .item_size(card_dim[1] + spacing)
+            .scrollbar_next_to()
+            .w_h(grid_dim[0], grid_dim[1])
+            .mid_top_with_margin_on(id, grid_top)
+            .parent(id)
+            .set(state.ids.grid, ui);
+        let mut card_events = vec![];
+        let badge_r = font_size as Scalar;
+        while let Some(list_event) = events.next(ui, |_| false) {
+            if let list_select::Event::Item(item) = list_event {
+                let (row, row_id) = (item.i, item.widget_id);
+                item.set(widget::Rectangle::fill_with([grid_dim[0], card_dim[1] + spacing],
+                                                      color::TRANSPARENT),
+                         ui);
+                for col in 0..cols {
+                    let i = match shown.get(row * cols + col) {
+                        Some(&i) => i,
+                        None => break,
+                    };
+                    let (image_id, src_rect) = self.cards[i].image();
+                    let face = BorderedImage::new(image_id)
+                        .w_h(card_dim[0], card_dim[1])
+                        .top_left_with_margins_on(row_id,
+                                                  spacing / 2.0,
+                                                  col as Scalar * (card_dim[0] + spacing))
+                        .parent(row_id);
+                    let face = match src_rect {
+                        Some(src_rect) => face.source_rectangle(src_rect),
+                        None => face,
+                    };
+                    if face.set(state.ids.faces[i], ui).was_clicked() {
+                        card_events.push(CollectionEvent::Click(i));
+                    }
+                    if ui.widget_input(state.ids.faces[i]).drags().left().next().is_some() {
+                        card_events.push(CollectionEvent::Drag(i));
+                    }
+                    let count = match self.owned.and_then(|owned| owned.get(i)) {
+                        Some(count) => count.to_string(),
+                        None => continue,
+                    };
+                    widget::Circle::fill(badge_r)
+                        .color(self.style.badge_color(&ui.theme))
+                        .top_right_with_margins_on(state.ids.faces[i], 0.0, 0.0)
+                        .parent(row_id)
+                        .graphics_for(state.ids.faces[i])
+                        .set(state.ids.badges[i], ui);
+                    widget::Text::new(&count)
+                        .font_size(font_size)
+                        .color(label_color)
+                        .and_then(font_id, widget::Text::font_id)
+                        .middle_of(state.ids.badges[i])
+                        .parent(row_id)
+                        .graphics_for(state.ids.faces[i])
+                        .set(state.ids.counts[i], ui);
+                }
+            }
+        }
+        if let Some(s) = scrollbar {
+            s.set(ui)
+        }
+        card_events
+    }
+}
+
+impl<'a, C: Collectable + 'a> Colorable for CardCollection<'a, C> {
+    builder_method!(color { style.color = Some(Color) });
+}
@@ -0,0 +1,362 @@
+use conrod_core::{widget, color, text, Color, Colorable, FontSize, Positionable, Scalar, Sizeable,
+                  Widget};
+use custom_widget::bordered_image::TimesClicked;
+use custom_widget::card_flip::Face;
+use custom_widget::nine_slice::{NineSlice, NineSliceImage};
+use custom_widget::rich_text::{IconMap, RichText};
+use custom_widget::tooltip::Tooltips;
+use std::collections::HashMap;
+
+/// How rare a card is, which picks the frame it is drawn with.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Mythic,
+}
+impl Default for Rarity {
+    fn default() -> Self {
+        Rarity::Common
+    }
+}
+impl Rarity {
+    /// The frame color used when the theme doesn't give one.
+    pub fn color(&self) -> Color {
+        match *self {
+            Rarity::Common => color::DARK_CHARCOAL,
+            Rarity::Uncommon => color::LIGHT_GREY,
+            Rarity::Rare => color::rgb(0.85, 0.65, 0.13),
+            Rarity::Mythic => color::rgb(0.9, 0.35, 0.1),
+        }
+    }
+}
+
+/// What a `CardFace` shows. Text may use `RichText` markup, so costs such as `{mana}{mana}` can
+/// be drawn as icons.
+pub trait CardData {
+    fn title(&self) -> &str;
+    fn art(&self) -> Face;
+    fn cost(&self) -> Option<&str> {
+        None
+    }
+    /// E.g. "Creature - Goblin".
+    fn type_line(&self) -> Option<&str> {
+        None
+    }
+    fn rules(&self) -> &str {
+        ""
+    }
+    /// E.g. "2/3" for power and toughness.
+    fn stats(&self) -> Option<&str> {
+        None
+    }
+    fn rarity(&self) -> Rarity {
+        Rarity::Common
+    }
+    /// The markup shown by `CardFace::tooltip`: the title, type line and rules.
+    fn tooltip(&self) -> String {
+        let mut text = format!("[b]{}[/b]", self.title());
+        if let Some(type_line) = self.type_line() {
+            text.push('\n');
+            text.push_str(type_line);
+        }
+        if !self.rules().is_empty() {
+            text.push('\n');
+            text.push_str(self.rules());
+        }
+        text
+    }
+}
+
+/// Frame art and colors per rarity.
+#[derive(Clone, Debug, Default)]
+pub struct FrameTheme {
+    frames: HashMap<Rarity, NineSliceImage>,
+    colors: HashMap<Rarity, Color>,
+}
+impl FrameTheme {
+    pub fn new() -> Self {
+        FrameTheme::default()
+    }
+    /// Draw cards of `rarity` inside `frame` instead of a plain border.
+    pub fn frame(mut self, rarity: Rarity, frame: NineSliceImage) -> Self {
+        self.frames.insert(rarity, frame);
+        self
+    }
+    /// The border color of cards of `rarity` without frame art.
+    pub fn color(mut self, rarity: Rarity, color: Color) -> Self {
+        self.colors.insert(rarity, color);
+        self
+    }
+    pub fn frame_for(&self, rarity: Rarity) -> Option<NineSliceImage> {
+        self.frames.get(&rarity).cloned()
+    }
+    pub fn color_for(&self, rarity: Rarity) -> Color {
+        self.colors.get(&rarity).cloned().unwrap_or(rarity.color())
+    }
+}
+
+/// A card drawn from its `CardData`: title and cost along the top, then the art, the type line,
+/// the rules text and the stats. Everything is laid out in proportion to the card's size.
+#[derive(WidgetCommon)]
+pub struct CardFace<'a, C: CardData + 'a> {
+    /// An object that handles some of the dirty work of rendering a GUI. We don't
+    /// really have to worry about it.
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    pub card: &'a C,
+    /// Show only the art and cost, e.g. for cards in a hand or on the board.
+    pub compact: bool,
+    pub theme: Option<&'a FrameTheme>,
+    pub icons: Option<&'a IconMap>,
+    /// Where to ask for the card's tooltip while it is hovered.
+    pub tooltips: Option<&'a Tooltips>,
+    /// See the Style struct below.
+    style: Style,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
+pub struct Style {
+    /// Color of the card under the art and text.
+    #[conrod(default = "theme.shape_color")]
+    pub color: Option<Color>,
+    /// Color of the box behind the rules text.
+    #[conrod(default = "theme.background_color")]
+    pub panel_color: Option<Color>,
+    #[conrod(default = "theme.label_color")]
+    pub text_color: Option<Color>,
+    /// Width of the plain border as a fraction of the card width.
+    #[conrod(default = "0.04")]
+    pub border: Option<Scalar>,
+    /// Specify a unique font for the text.
+    #[conrod(default = "theme.font_id")]
+    pub font_id: Option<Option<text::font::Id>>,
+}
+
+widget_ids! {
+    struct Ids {
+        frame,
+        frame_art,
+        art,
+        title,
+        cost,
+        type_line,
+        rules_box,
+        rules,
+        stats,
+    }
+}
+
+/// Represents the unique, cached state for our CardFace widget.
+pub struct State {
+    ids: Ids,
+}
+
+impl<'a, C: CardData + 'a> CardFace<'a, C> {
+    /// Create a button context to be built upon.
+    pub fn new(card: &'a C) -> Self {
+        CardFace {
+            card: card,
+            compact: false,
+            theme: None,
+            icons: None,
+            tooltips: None,
+            common: widget::CommonBuilder::default(),
+            style: Style::default(),
+        }
+    }
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+    /// Frame art and colors per rarity.
+    pub fn theme(mut self, theme: &'a FrameTheme) -> Self {
+        self.theme = Some(theme);
+        self
+    }
+    /// Icons that `{name}` markup in the cost and rules refers to.
+    pub fn icons(mut self, icons: &'a IconMap) -> Self {
+        self.icons = Some(icons);
+        self
+    }
+    /// Show `CardData::tooltip` while the card is hovered, e.g. for compact cards.
+    pub fn tooltip(mut self, tooltips: &'a Tooltips) -> Self {
+        self.tooltips = Some(tooltips);
+        self
+    }
+    /// Specify the font used for the text.
+    pub fn font_id(mut self, font_id: text::font::Id) -> Self {
+        self.style.font_id = Some(Some(font_id));
+        self
+    }
+    builder_methods!{
+        pub panel_color { style.panel_color = Some(Color) }
+        pub text_color { style.text_color = Some(Color) }
+        pub border { style.border = Some(Scalar) }
+    }
+}
+
+/// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
+/// documentation for more details.
+impl<'a, C: CardData + 'a> Widget for CardFace<'a, C> {
+    /// The State struct that we defined above.
+    type State = State;
+    /// The Style struct that we defined using the `widget_style!` macro.
+    type Style = Style;
+    /// The event produced by instantiating the widget.
+    type Event = TimesClicked;
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State { ids: Ids::new(id_gen) }
+    }
+
+    fn style(&self) -> Self::Style {
+        self.style.clone()
+    }
+
+    /// Update the state of the button by handling any input that has occurred since the last
+    /// update.
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { id, state, rect, ui, .. } = args;
+        let clicks = {
+            let input = ui.widget_input(id);
+            input.clicks().left().count() + input.taps().count()
+        };
+        let card = self.card;
+        if let Some(tooltips) = self.tooltips {
+            if ui.widget_input(id).mouse().is_some() {
+                tooltips.request(id, &card.tooltip());
+            }
+        }
+        let (w, h) = (rect.w(), rect.h());
+        let font_id = self.style.font_id(&ui.theme);
+        let text_color = self.style.text_color(&ui.theme);
+        // Font sizes follow the card height so the layout holds at any size.
+        let font_size = |fraction: f64| (h * fraction).max(1.0) as FontSize;
+        let pad = w * 0.06;
+        let inner_w = w - 2.0 * pad;
+
+        let rarity = card.rarity();
+        let frame_art = self.theme.and_then(|t| t.frame_for(rarity));
+        let frame_color = self.theme.map_or(rarity.color(), |t| t.color_for(rarity));
+        let border = if frame_art.is_some() {
+            0.0
+        } else {
+            w * self.style.border(&ui.theme)
+        };
+        widget::BorderedRectangle::new([w, h])
+            .color(self.style.color(&ui.theme))
+            .border(border)
+            .border_color(frame_color)
+            .middle_of(id)
+            .parent(id)
+            .graphics_for(id)
+            .set(state.ids.frame, ui);
+        if let Some(frame_art) = frame_art {
+            NineSlice::new(frame_art)
+                .wh_of(id)
+                .middle_of(id)
+                .parent(id)
+                .graphics_for(id)
+                .set(state.ids.frame_art, ui);
+        }
+
+        let art = card.art();
+        let art_h = if self.compact { h - 2.0 * pad } else { h * 0.42 };
+        let art_top = if self.compact { pad } else { h * 0.12 };
+        widget::Image::new(art.image)
+            .and_then(art.src_rect, widget::Image::source_rectangle)
+            .w_h(inner_w, art_h)
+            .mid_top_with_margin_on(id, art_top)
+            .parent(id)
+            .graphics_for(id)
+            .set(state.ids.art, ui);
+
+        if let Some(cost) = card.cost() {
+            let (cost_w, cost_size) = if self.compact {
+                (inner_w * 0.4, font_size(0.1))
+            } else {
+                (inner_w * 0.25, font_size(0.06))
+            };
+            let cost = RichText::new(cost)
+                .w_h(cost_w, h * 0.1)
+                .font_size(cost_size)
+                .color(text_color)
+                .and_then(font_id, RichText::font_id)
+                .parent(id)
+                .graphics_for(id);
+            let cost = if self.compact {
+                cost.top_left_with_margins_on(id, pad, pad)
+            } else {
+                cost.top_right_with_margins_on(id, pad, pad)
+            };
+            match self.icons {
+                Some(icons) => cost.icons(icons).set(state.ids.cost, ui),
+                None => cost.set(state.ids.cost, ui),
+            };
+        }
+        if self.compact {
+            return TimesClicked(clicks as u16);
+        }
+
+        widget::Text::new(card.title())
+            .w_h(inner_w * 0.75, h * 0.08)
+            .font_size(font_size(0.055))
+            .color(text_color)
+            .and_then(font_id, widget::Text::font_id)
+            .no_line_wrap()
+            .top_left_with_margins_on(id, pad, pad)
+            .parent(id)
+            .graphics_for(id)
+            .set(state.ids.title, ui);
+        if let Some(type_line) = card.type_line() {
+            widget::Text::new(type_line)
+                .w_h(inner_w, h * 0.06)
+                .font_size(font_size(0.045))
+                .color(text_color)
+                .and_then(font_id, widget::Text::font_id)
+                .no_line_wrap()
+                .top_left_with_margins_on(id, h * 0.56, pad)
+                .parent(id)
+                .graphics_for(id)
+                .set(state.ids.type_line, ui);
+        }
+        widget::Rectangle::fill_with([inner_w, h * 0.27],
+                                     self.style.panel_color(&ui.theme))
+            .mid_top_with_margin_on(id, h * 0.63)
+            .parent(id)
+            .graphics_for(id)
+            .set(state.ids.rules_box, ui);
+        let rules = RichText::new(card.rules())
+            .w_h(inner_w - pad, h * 0.25)
+            .font_size(font_size(0.04))
+            .color(text_color)
+            .and_then(font_id, RichText::font_id)
+            .middle_of(state.ids.rules_box)
+            .parent(id)
+            .graphics_for(id);
+        match self.icons {
+            Some(icons) => rules.icons(icons).set(state.ids.rules, ui),
+            None => rules.set(state.ids.rules, ui),
+        };
+        if let Some(stats) = card.stats() {
+            widget::Text::new(stats)
+                .w_h(inner_w * 0.4, h * 0.07)
+                .font_size(font_size(0.055))
+                .color(text_color)
+                .and_then(font_id, widget::Text::font_id)
+                .right_justify()
+                .no_line_wrap()
+                .bottom_right_with_margins_on(id, pad * 0.5, pad)
+                .parent(id)
+                .graphics_for(id)
+                .set(state.ids.stats, ui);
+        }
+        TimesClicked(clicks as u16)
+    }
+}
+
+impl<'a, C: CardData + 'a> Colorable for CardFace<'a, C> {
+    builder_method!(color { style.color = Some(Color) });
+}
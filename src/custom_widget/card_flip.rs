@@ -0,0 +1,203 @@
+use conrod_core::{widget, image, Positionable, Rect, Sizeable, Widget};
+use sprite::{spriteable_rect, Spriteable};
+use clock::{secs, since, Clock, SYSTEM_CLOCK};
+use tween::Easing;
+use std::time::{Duration, Instant};
+
+/// One side of a card: an image and, for sprite sheets, the part of it to draw.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Face {
+    pub image: image::Id,
+    pub src_rect: Option<Rect>,
+}
+impl Face {
+    pub fn new(image: image::Id) -> Self {
+        Face {
+            image: image,
+            src_rect: None,
+        }
+    }
+    /// Frame `index` of a sprite sheet.
+    pub fn from_sprite<H: Spriteable>(image: image::Id, sprite: H, index: f64) -> Self {
+        let r = spriteable_rect(sprite, index);
+        Face {
+            image: image,
+            src_rect: Some(Rect::from_corners(r.0, r.1)),
+        }
+    }
+}
+
+/// A card that turns over between its back and front by narrowing to nothing and widening again
+/// with the other face showing.
+#[derive(WidgetCommon)]
+pub struct CardFlip<'a> {
+    /// An object that handles some of the dirty work of rendering a GUI. We don't
+    /// really have to worry about it.
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    pub front: Face,
+    pub back: Face,
+    /// The side that should be showing. When `None`, clicking the card turns it over.
+    pub face_up: Option<bool>,
+    pub clock: &'a Clock,
+    /// See the Style struct below.
+    style: Style,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
+pub struct Style {
+    /// How long a whole flip takes.
+    #[conrod(default = "Duration::from_millis(400)")]
+    pub duration: Option<Duration>,
+    #[conrod(default = "Easing::QuadInOut")]
+    pub easing: Option<Easing>,
+}
+
+/// The event produced by `CardFlip` on every update.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Flip {
+    /// Not moving; `true` when the front is showing.
+    Showing(bool),
+    /// Part way through turning over, with the eased progress from 0 to 1.
+    Turning(f64),
+    /// The flip finished during this update and the given side is now showing.
+    Revealed(bool),
+}
+impl Flip {
+    /// `true` once the card has stopped turning.
+    pub fn is_finished(&self) -> bool {
+        match *self {
+            Flip::Turning(_) => false,
+            Flip::Showing(_) | Flip::Revealed(_) => true,
+        }
+    }
+}
+
+widget_ids! {
+    struct Ids {
+        face,
+    }
+}
+
+/// Represents the unique, cached state for our CardFlip widget.
+pub struct State {
+    ids: Ids,
+    /// The side showing, or being turned to.
+    face_up: bool,
+    /// When the current flip started.
+    start: Option<Instant>,
+}
+
+impl<'a> CardFlip<'a> {
+    /// Create a button context to be built upon.
+    pub fn new(front: Face, back: Face) -> Self {
+        CardFlip {
+            front: front,
+            back: back,
+            face_up: None,
+            clock: &SYSTEM_CLOCK,
+            common: widget::CommonBuilder::default(),
+            style: Style::default(),
+        }
+    }
+    /// Show the front (`true`) or back. Changing this turns the card over.
+    pub fn face_up(mut self, face_up: bool) -> Self {
+        self.face_up = Some(face_up);
+        self
+    }
+    /// Read the time from `clock` instead of the system clock.
+    pub fn clock(mut self, clock: &'a Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+    builder_methods!{
+        pub duration { style.duration = Some(Duration) }
+        pub easing { style.easing = Some(Easing) }
+    }
+}
+
+/// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
+/// documentation for more details.
+impl<'a> Widget for CardFlip<'a> {
+    /// The State struct that we defined above.
+    type State = State;
+    /// The Style struct that we defined using the `widget_style!` macro.
+    type Style = Style;
+    /// The event produced by instantiating the widget.
+    type Event = Flip;
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State {
+            ids: Ids::new(id_gen),
+            face_up: self.face_up.unwrap_or(false),
+            start: None,
+        }
+    }
+
+    fn style(&self) -> Self::Style {
+        self.style.clone()
+    }
+
+    /// Update the state of the button by handling any input that has occurred since the last
+    /// update.
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { id, state, rect, ui, .. } = args;
+        let duration = secs(self.style.duration(&ui.theme));
+        let easing = self.style.easing(&ui.theme);
+        let clicked = {
+            let input = ui.widget_input(id);
+            input.clicks().left().count() + input.taps().count() > 0
+        };
+        let target = match self.face_up {
+            Some(face_up) => face_up,
+            None if clicked => !state.face_up,
+            None => state.face_up,
+        };
+
+        // Raw progress of the flip under way, if any.
+        let mut t = state.start.map(|start| if duration > 0.0 {
+                                         secs(since(self.clock, start)) / duration
+                                     } else {
+                                         1.0
+                                     });
+        if target != state.face_up {
+            // Turning back part way through plays the rest of the flip in reverse.
+            let done = t.map_or(0.0, |t| 1.0 - t.min(1.0));
+            let now = self.clock.now();
+            let start = now - Duration::from_millis((done * duration * 1000.0) as u64);
+            state.update(|state| {
+                             state.face_up = target;
+                             state.start = Some(start);
+                         });
+            t = Some(done);
+        }
+
+        let (face_up, event) = match t {
+            None => (state.face_up, Flip::Showing(state.face_up)),
+            Some(t) if t >= 1.0 => {
+                state.update(|state| state.start = None);
+                (state.face_up, Flip::Revealed(state.face_up))
+            }
+            Some(t) => {
+                let p = easing.apply(t);
+                // The old side narrows during the first half, the new side widens in the second.
+                let showing = if p < 0.5 { !state.face_up } else { state.face_up };
+                (showing, Flip::Turning(p))
+            }
+        };
+        let scale = match event {
+            Flip::Turning(p) => (1.0 - 2.0 * p).abs(),
+            _ => 1.0,
+        };
+        let face = if face_up { self.front } else { self.back };
+        let (_, _, w, h) = rect.x_y_w_h();
+        widget::Image::new(face.image)
+            .and_then(face.src_rect, widget::Image::source_rectangle)
+            .w_h(w * scale, h)
+            .middle_of(id)
+            .parent(id)
+            .graphics_for(id)
+            .set(state.ids.face, ui);
+        event
+    }
+}
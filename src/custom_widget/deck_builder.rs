@@ -0,0 +1,488 @@
+use conrod_core::{widget, color, text, Color, Colorable, FontSize, Labelable, Positionable, Scalar,
+                  Sizeable, Widget};
+use custom_widget::card_collection::{CardCollection, Collectable, CollectionEvent,
+                                     CollectionQuery};
+use custom_widget::image_panels::list_select;
+use text::{font_of, wrapped_size};
+use std::fmt;
+
+/// The cards in a deck and how many copies of each, in the order they were first added.
+/// Cards are indices in the collection.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Deck {
+    entries: Vec<(usize, usize)>,
+}
+
+impl Deck {
+    pub fn new() -> Self {
+        Deck::default()
+    }
+    /// Add a copy of `card`.
+    pub fn add(&mut self, card: usize) {
+        match self.entries.iter().position(|&(c, _)| c == card) {
+            Some(i) => self.entries[i].1 += 1,
+            None => self.entries.push((card, 1)),
+        }
+    }
+    /// Take out a copy of `card`. Returns whether there was one.
+    pub fn remove(&mut self, card: usize) -> bool {
+        match self.entries.iter().position(|&(c, _)| c == card) {
+            Some(i) => {
+                self.entries[i].1 -= 1;
+                if self.entries[i].1 == 0 {
+                    self.entries.remove(i);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+    pub fn count(&self, card: usize) -> usize {
+        self.entries.iter().find(|&&(c, _)| c == card).map_or(0, |&(_, n)| n)
+    }
+    /// The number of cards, counting every copy.
+    pub fn total(&self) -> usize {
+        self.entries.iter().map(|&(_, n)| n).sum()
+    }
+    /// `(card, copies)` for every card in the deck.
+    pub fn entries(&self) -> &[(usize, usize)] {
+        &self.entries
+    }
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// A way in which a deck breaks the rules.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    TooManyCopies {
+        card: usize,
+        name: String,
+        count: usize,
+        max: usize,
+    },
+    Banned { card: usize, name: String },
+    TooFewCards { count: usize, min: usize },
+    TooManyCards { count: usize, max: usize },
+    /// Any other rule, about one card or the whole deck.
+    Other {
+        card: Option<usize>,
+        message: String,
+    },
+}
+
+impl Violation {
+    /// The card at fault, if the violation is about a single card.
+    pub fn card(&self) -> Option<usize> {
+        match *self {
+            Violation::TooManyCopies { card, .. } |
+            Violation::Banned { card, .. } => Some(card),
+            Violation::Other { card, .. } => card,
+            Violation::TooFewCards { .. } |
+            Violation::TooManyCards { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Violation::TooManyCopies { ref name, count, max, .. } => {
+                write!(f, "{} copies of {}, at most {} allowed", count, name, max)
+            }
+            Violation::Banned { ref name, .. } => write!(f, "{} is banned", name),
+            Violation::TooFewCards { count, min } => {
+                write!(f, "{} cards, at least {} needed", count, min)
+            }
+            Violation::TooManyCards { count, max } => {
+                write!(f, "{} cards, at most {} allowed", count, max)
+            }
+            Violation::Other { ref message, .. } => write!(f, "{}", message),
+        }
+    }
+}
+
+/// The rules a deck is checked against by the `DeckBuilder`.
+pub trait DeckRules<C: Collectable> {
+    /// Everything wrong with `deck`, whose entries are indices in `cards`. Empty if the deck
+    /// is legal.
+    fn validate(&self, deck: &Deck, cards: &[C]) -> Vec<Violation>;
+}
+
+/// The usual constructed-format rules: a minimum and maximum deck size, a limit on copies of
+/// each card and a list of banned cards.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StandardRules {
+    pub min_size: usize,
+    pub max_size: Option<usize>,
+    pub max_copies: usize,
+    /// Banned cards by name.
+    pub banned: Vec<String>,
+}
+
+impl StandardRules {
+    pub fn new(min_size: usize, max_copies: usize) -> Self {
+        StandardRules {
+            min_size: min_size,
+            max_size: None,
+            max_copies: max_copies,
+            banned: vec![],
+        }
+    }
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+    /// Ban the card called `name`.
+    pub fn ban(mut self, name: &str) -> Self {
+        self.banned.push(name.to_owned());
+        self
+    }
+}
+
+impl<C: Collectable> DeckRules<C> for StandardRules {
+    fn validate(&self, deck: &Deck, cards: &[C]) -> Vec<Violation> {
+        let mut violations = vec![];
+        for &(card, count) in deck.entries() {
+            let name = cards.get(card).map_or("", |c| c.name());
+            if self.banned.iter().any(|b| b == name) {
+                violations.push(Violation::Banned {
+                                    card: card,
+                                    name: name.to_owned(),
+                                });
+            }
+            if count > self.max_copies {
+                violations.push(Violation::TooManyCopies {
+                                    card: card,
+                                    name: name.to_owned(),
+                                    count: count,
+                                    max: self.max_copies,
+                                });
+            }
+        }
+        let total = deck.total();
+        if total < self.min_size {
+            violations.push(Violation::TooFewCards {
+                                count: total,
+                                min: self.min_size,
+                            });
+        }
+        if let Some(max) = self.max_size {
+            if total > max {
+                violations.push(Violation::TooManyCards {
+                                    count: total,
+                                    max: max,
+                                });
+            }
+        }
+        violations
+    }
+}
+
+/// A change the user made to the deck, by card index.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DeckEvent {
+    Added(usize),
+    Removed(usize),
+}
+
+/// The collection on the left and the deck list on the right. Clicking a card or dragging it
+/// onto the deck adds a copy; clicking a deck row or dragging it off the deck takes one out.
+///
+/// The deck list shows the copies of each card, the running total and, underneath, whatever
+/// the `DeckRules` find wrong. Rows of cards at fault are drawn in `error_color`.
+#[derive(WidgetCommon)]
+pub struct DeckBuilder<'a, C: Collectable + 'a> {
+    /// An object that handles some of the dirty work of rendering a GUI. We don't
+    /// really have to worry about it.
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    cards: &'a [C],
+    pub deck: &'a mut Deck,
+    pub query: &'a mut CollectionQuery,
+    rules: &'a DeckRules<C>,
+    /// The filter chips offered above the collection.
+    pub chips: &'a [&'a str],
+    /// How many copies of each card are owned, by index in the cards.
+    pub owned: Option<&'a [usize]>,
+    /// See the Style struct below.
+    style: Style,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
+pub struct Style {
+    /// The size of the cards in the collection.
+    #[conrod(default = "[100.0, 140.0]")]
+    pub card_dim: Option<[f64; 2]>,
+    /// How much of the width the collection takes.
+    #[conrod(default = "0.65")]
+    pub collection_fraction: Option<f64>,
+    #[conrod(default = "24.0")]
+    pub row_h: Option<Scalar>,
+    /// Color of the deck list.
+    #[conrod(default = "theme.background_color")]
+    pub color: Option<Color>,
+    /// Color of the deck rows.
+    #[conrod(default = "theme.shape_color")]
+    pub row_color: Option<Color>,
+    /// Color of the rows at fault and of the violations.
+    #[conrod(default = "color::RED")]
+    pub error_color: Option<Color>,
+    #[conrod(default = "theme.label_color")]
+    pub label_color: Option<Color>,
+    #[conrod(default = "theme.font_size_small")]
+    pub label_font_size: Option<FontSize>,
+    /// Specify a unique font for the labels.
+    #[conrod(default = "theme.font_id")]
+    pub label_font_id: Option<Option<text::font::Id>>,
+}
+
+widget_ids! {
+    struct Ids {
+        collection,
+        deck_back,
+        total,
+        deck_list,
+        violations,
+        ghost,
+    }
+}
+
+/// Where a card is being dragged from.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Pane {
+    Collection,
+    Deck,
+}
+
+/// Represents the unique, cached state for our DeckBuilder widget.
+pub struct State {
+    ids: Ids,
+    /// The card being dragged and where from.
+    dragging: Option<(Pane, usize)>,
+}
+
+impl<'a, C: Collectable + 'a> DeckBuilder<'a, C> {
+    /// Create a button context to be built upon.
+    pub fn new(cards: &'a [C],
+               deck: &'a mut Deck,
+               query: &'a mut CollectionQuery,
+               rules: &'a DeckRules<C>)
+               -> Self {
+        DeckBuilder {
+            cards: cards,
+            deck: deck,
+            query: query,
+            rules: rules,
+            chips: &[],
+            owned: None,
+            common: widget::CommonBuilder::default(),
+            style: Style::default(),
+        }
+    }
+    /// Offer a chip for each of `chips` above the collection.
+    pub fn chips(mut self, chips: &'a [&'a str]) -> Self {
+        self.chips = chips;
+        self
+    }
+    /// Show `owned[i]` in a badge on card `i` of the collection.
+    pub fn owned(mut self, owned: &'a [usize]) -> Self {
+        self.owned = Some(owned);
+        self
+    }
+    /// Specify the font used for the labels.
+    pub fn label_font_id(mut self, font_id: text::font::Id) -> Self {
+        self.style.label_font_id = Some(Some(font_id));
+        self
+    }
+    builder_methods!{
+        pub card_dim { style.card_dim = Some([f64; 2]) }
+        pub collection_fraction { style.collection_fraction = Some(f64) }
+        pub row_h { style.row_h = Some(Scalar) }
+        pub row_color { style.row_color = Some(Color) }
+        pub error_color { style.error_color = Some(Color) }
+        pub label_color { style.label_color = Some(Color) }
+        pub label_font_size { style.label_font_size = Some(FontSize) }
+    }
+}
+
+/// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
+/// documentation for more details.
+impl<'a, C: Collectable + 'a> Widget for DeckBuilder<'a, C> {
+    /// The State struct that we defined above.
+    type State = State;
+    /// The Style struct that we defined using the `widget_style!` macro.
+    type Style = Style;
+    /// The event produced by instantiating the widget.
+    type Event = Vec<DeckEvent>;
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State {
+            ids: Ids::new(id_gen),
+            dragging: None,
+        }
+    }
+
+    fn style(&self) -> Self::Style {
+        self.style.clone()
+    }
+
+    /// Update the state of the button by handling any input that has occurred since the last
+    /// update.
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { id, state, rect, ui, .. } = args;
+        let cards = self.cards;
+        let deck = self.deck;
+        let label_color = self.style.label_color(&ui.theme);
+        let error_color = self.style.error_color(&ui.theme);
+        let font_size = self.style.label_font_size(&ui.theme);
+        let font_id = self.style.label_font_id(&ui.theme);
+        let mut events = vec![];
+        let mut dragging = state.dragging;
+
+        let collection_w = rect.w() * self.style.collection_fraction(&ui.theme);
+        let collection = CardCollection::new(cards, self.query)
+            .chips(self.chips)
+            .card_dim(self.style.card_dim(&ui.theme))
+            .label_color(label_color)
+            .label_font_size(font_size)
+            .w_h(collection_w, rect.h())
+            .top_left_of(id)
+            .parent(id);
+        let collection = match self.owned {
+            Some(owned) => collection.owned(owned),
+            None => collection,
+        };
+        let collection = match font_id {
+            Some(font_id) => collection.label_font_id(font_id),
+            None => collection,
+        };
+        for event in collection.set(state.ids.collection, ui) {
+            match event {
+                CollectionEvent::Click(card) => {
+                    deck.add(card);
+                    events.push(DeckEvent::Added(card));
+                }
+                CollectionEvent::Drag(card) => {
+                    if dragging.is_none() {
+                        dragging = Some((Pane::Collection, card));
+                    }
+                }
+            }
+        }
+
+        // The deck list: the total on top, the rows, then the violations.
+        let deck_w = rect.w() - collection_w;
+        widget::Rectangle::fill_with([deck_w, rect.h()], self.style.color(&ui.theme))
+            .top_right_of(id)
+            .parent(id)
+            .set(state.ids.deck_back, ui);
+        let row_h = self.style.row_h(&ui.theme);
+        widget::Text::new(&format!("{} cards", deck.total()))
+            .font_size(font_size)
+            .color(label_color)
+            .and_then(font_id, widget::Text::font_id)
+            .mid_top_with_margin_on(state.ids.deck_back, row_h * 0.25)
+            .parent(id)
+            .set(state.ids.total, ui);
+        let violations = self.rules.validate(deck, cards);
+        let messages = violations.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("\n");
+        // The messages wrap, so measure them rather than counting one line each.
+        let messages_w = deck_w - row_h * 0.5;
+        let messages_h = match font_of(ui, font_id) {
+            _ if violations.is_empty() => 0.0,
+            Some(font) => wrapped_size(font, font_size, &messages, messages_w, 1.0)[1],
+            None => violations.len() as Scalar * font_size as Scalar * 1.2,
+        };
+        if !violations.is_empty() {
+            widget::Text::new(&messages)
+                .w(messages_w)
+                .font_size(font_size)
+                .color(error_color)
+                .and_then(font_id, widget::Text::font_id)
+                .mid_bottom_with_margin_on(state.ids.deck_back, row_h * 0.25)
+                .parent(id)
+                .set(state.ids.violations, ui);
+        }
+
+        let entries = deck.entries().to_vec();
+        let list_h = (rect.h() - row_h * 1.5 - messages_h - row_h * 0.25).max(0.0);
+        let (mut list_events, scrollbar) = list_select::ListSelect::single(entries.len())
+            .flow_down()
+            .item_size(row_h)
+            .scrollbar_next_to()
+            .w_h(deck_w, list_h)
+            .mid_top_with_margin_on(state.ids.deck_back, row_h * 1.5)
+            .parent(id)
+            .set(state.ids.deck_list, ui);
+        while let Some(list_event) = list_events.next(ui, |_| false) {
+            if let list_select::Event::Item(item) = list_event {
+                let (card, count) = entries[item.i];
+                let row_id = item.widget_id;
+                let name = cards.get(card).map_or("", |c| c.name());
+                let at_fault = violations.iter().any(|v| v.card() == Some(card));
+                let label = format!("{} x{}", name, count);
+                let row = widget::Button::new()
+                    .label(&label)
+                    .label_color(if at_fault { error_color } else { label_color })
+                    .label_font_size(font_size)
+                    .color(self.style.row_color(&ui.theme));
+                let row = match font_id {
+                    Some(font_id) => row.label_font_id(font_id),
+                    None => row,
+                };
+                if item.set(row, ui).was_clicked() && deck.remove(card) {
+                    events.push(DeckEvent::Removed(card));
+                }
+                let dragged = ui.widget_input(row_id).drags().left().next().is_some();
+                if dragging.is_none() && dragged {
+                    dragging = Some((Pane::Deck, card));
+                }
+            }
+        }
+        if let Some(s) = scrollbar {
+            s.set(ui)
+        }
+
+        // Follow the mouse with the dragged card and drop it when the button is let go.
+        if let Some((from, card)) = dragging {
+            let xy = ui.global_input().current.mouse.xy;
+            let released = ui.global_input().current.mouse.buttons.left().is_up();
+            let over_deck = ui.rect_of(state.ids.deck_back).map_or(false, |r| r.is_over(xy));
+            if released {
+                dragging = None;
+                match from {
+                    Pane::Collection if over_deck => {
+                        deck.add(card);
+                        events.push(DeckEvent::Added(card));
+                    }
+                    Pane::Deck if !over_deck => {
+                        if deck.remove(card) {
+                            events.push(DeckEvent::Removed(card));
+                        }
+                    }
+                    _ => {}
+                }
+            } else if let Some(c) = cards.get(card) {
+                let (image_id, src_rect) = c.image();
+                let dim = self.style.card_dim(&ui.theme);
+                widget::Image::new(image_id)
+                    .and_then(src_rect, widget::Image::source_rectangle)
+                    .w_h(dim[0] * 0.8, dim[1] * 0.8)
+                    .x_y(xy[0], xy[1])
+                    .parent(id)
+                    .graphics_for(id)
+                    .set(state.ids.ghost, ui);
+            }
+        }
+        if dragging != state.dragging {
+            state.update(|state| state.dragging = dragging);
+        }
+        events
+    }
+}
+
+impl<'a, C: Collectable + 'a> Colorable for DeckBuilder<'a, C> {
+    builder_method!(color { style.color = Some(Color) });
+}
@@ -21,6 +21,8 @@ pub struct DragDropList<'a, T, W>
     values: &'a mut Vec<T>,
     widget_closure: Box<Fn(T) -> W>,
     item_width: f64,
+    /// Written with the item being dragged on every update, `None` when there is none.
+    held: Option<&'a mut Option<T>>,
 }
 
 #[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
@@ -189,8 +191,14 @@ impl<'a, T, W> DragDropList<'a, T, W>
             values: values,
             widget_closure: widget_closure,
             item_width: item_width,
+            held: None,
         }
     }
+    /// Keep `held` up to date with the item being dragged, e.g. for `Board::external_drag`.
+    pub fn held(mut self, held: &'a mut Option<T>) -> Self {
+        self.held = Some(held);
+        self
+    }
     builder_methods!{
         pub exit_id { style.exit_id = Option<Option<widget::Id>> }
     }
@@ -355,6 +363,15 @@ impl<'a, T, W> Widget for DragDropList<'a, T, W>
 
             }
         }
+        if let Some(held) = self.held {
+            *held = if mouse_down {
+                state.mouse_point
+                    .and_then(|(i, _)| state.temp.get(i))
+                    .map(|&(_, ref value)| value.clone())
+            } else {
+                None
+            };
+        }
         exit_id
     }
 }
@@ -0,0 +1,252 @@
+//! Cards flying from one widget to another, e.g. from the deck to a player's hand.
+//!
+//! Start a flight by pushing a `Flight` onto a `Vec` owned by the app, and set a `FlightOverlay`
+//! over that `Vec` after the widgets the cards fly between, so the cards draw on top of them.
+//! Each update the overlay draws every card that is on its way and hands back the ones that
+//! landed, so the destination can start showing them.
+use conrod_core::{widget, Point, Positionable, Sizeable, Widget};
+use clock::{secs, since, Clock, SYSTEM_CLOCK};
+use tween::{cubic_bezier, quadratic_bezier, Easing, Lerp};
+use std::time::{Duration, Instant};
+
+/// The way a card travels between the middles of two widgets.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Path {
+    Straight,
+    /// Bends towards a control point offset by this much from the middle of the straight line.
+    Quadratic(Point),
+    /// Leaves towards the start plus the first offset and arrives from the end plus the second.
+    Cubic(Point, Point),
+}
+impl Path {
+    /// The point `t` of the way from `from` to `to`.
+    pub fn point(&self, from: Point, to: Point, t: f64) -> Point {
+        let add = |a: Point, b: Point| [a[0] + b[0], a[1] + b[1]];
+        match *self {
+            Path::Straight => from.lerp(&to, t),
+            Path::Quadratic(offset) => {
+                quadratic_bezier(from, add(from.lerp(&to, 0.5), offset), to, t)
+            }
+            Path::Cubic(a, b) => cubic_bezier(from, add(from, a), add(to, b), to, t),
+        }
+    }
+}
+
+/// A card on its way from one widget to another.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Flight<T> {
+    pub item: T,
+    pub from: widget::Id,
+    pub to: widget::Id,
+    /// When the card leaves. Flights starting later than now aren't drawn yet, so a hand can be
+    /// dealt by staggering the start of each card.
+    pub start: Instant,
+    pub duration: Duration,
+    pub easing: Easing,
+    pub path: Path,
+    /// The card size at the start and end, as multiples of the overlay's `card_dim`.
+    pub scale: (f64, f64),
+    /// The overlay's widget id for the card, kept for the whole flight.
+    slot: Option<usize>,
+}
+impl<T> Flight<T> {
+    pub fn new(item: T, from: widget::Id, to: widget::Id, start: Instant) -> Self {
+        Flight {
+            item: item,
+            from: from,
+            to: to,
+            start: start,
+            duration: Duration::from_millis(500),
+            easing: Easing::CubicOut,
+            path: Path::Straight,
+            scale: (1.0, 1.0),
+            slot: None,
+        }
+    }
+    pub fn duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+    pub fn path(mut self, path: Path) -> Self {
+        self.path = path;
+        self
+    }
+    pub fn scale(mut self, from: f64, to: f64) -> Self {
+        self.scale = (from, to);
+        self
+    }
+    /// Raw progress from 0 to 1 after `elapsed`.
+    fn progress(&self, elapsed: Duration) -> f64 {
+        let d = secs(self.duration);
+        if d <= 0.0 { 1.0 } else { secs(elapsed) / d }
+    }
+}
+
+/// A card that landed during this update.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Arrival<T> {
+    pub item: T,
+    pub to: widget::Id,
+}
+
+/// The event produced by `FlightOverlay`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Flights<T> {
+    pub arrived: Vec<Arrival<T>>,
+    /// Flights still on their way or waiting to start.
+    pub in_flight: usize,
+}
+impl<T> Flights<T> {
+    /// `true` once every card has landed.
+    pub fn is_finished(&self) -> bool {
+        self.in_flight == 0
+    }
+}
+
+/// Draws the cards of a list of `Flight`s. The overlay's own position and size are not used;
+/// cards are placed where their flights have taken them.
+#[derive(WidgetCommon)]
+pub struct FlightOverlay<'a, T, W>
+    where T: Clone + 'a,
+          W: Widget
+{
+    /// An object that handles some of the dirty work of rendering a GUI. We don't
+    /// really have to worry about it.
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    pub flights: &'a mut Vec<Flight<T>>,
+    widget_closure: Box<Fn(T) -> W>,
+    pub clock: &'a Clock,
+    /// See the Style struct below.
+    style: Style,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
+pub struct Style {
+    /// The size of a card in flight at scale 1.
+    #[conrod(default = "[100.0, 140.0]")]
+    pub card_dim: Option<[f64; 2]>,
+}
+
+widget_ids! {
+    struct Ids {
+        cards[],
+    }
+}
+
+/// Represents the unique, cached state for our FlightOverlay widget.
+pub struct State {
+    ids: Ids,
+}
+
+impl<'a, T, W> FlightOverlay<'a, T, W>
+    where T: Clone + 'a,
+          W: Widget
+{
+    /// Create a button context to be built upon.
+    pub fn new(flights: &'a mut Vec<Flight<T>>, widget_closure: Box<Fn(T) -> W>) -> Self {
+        FlightOverlay {
+            flights: flights,
+            widget_closure: widget_closure,
+            clock: &SYSTEM_CLOCK,
+            common: widget::CommonBuilder::default(),
+            style: Style::default(),
+        }
+    }
+    /// Read the time from `clock` instead of the system clock. Flight start times should come
+    /// from the same clock.
+    pub fn clock(mut self, clock: &'a Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+    builder_methods!{
+        pub card_dim { style.card_dim = Some([f64; 2]) }
+    }
+}
+
+/// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
+/// documentation for more details.
+impl<'a, T, W> Widget for FlightOverlay<'a, T, W>
+    where T: Clone + 'a,
+          W: Widget
+{
+    /// The State struct that we defined above.
+    type State = State;
+    /// The Style struct that we defined using the `widget_style!` macro.
+    type Style = Style;
+    /// The event produced by instantiating the widget.
+    type Event = Flights<T>;
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State { ids: Ids::new(id_gen) }
+    }
+
+    fn style(&self) -> Self::Style {
+        self.style.clone()
+    }
+
+    /// Update the state of the button by handling any input that has occurred since the last
+    /// update.
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { id, state, ui, .. } = args;
+        let card_dim = self.style.card_dim(&ui.theme);
+        let now = self.clock.now();
+
+        // New flights take the lowest id no other flight holds, so a card keeps its widget
+        // state while the flights around it land.
+        let mut taken = self.flights.iter().filter_map(|f| f.slot).collect::<Vec<_>>();
+        for flight in self.flights.iter_mut().filter(|f| f.slot.is_none()) {
+            let slot = (0..).find(|s| !taken.contains(s)).unwrap();
+            taken.push(slot);
+            flight.slot = Some(slot);
+        }
+        let n_ids = taken.iter().max().map_or(0, |&s| s + 1);
+        if state.ids.cards.len() < n_ids {
+            let id_gen = &mut ui.widget_id_generator();
+            state.update(|state| state.ids.cards.resize(n_ids, id_gen));
+        }
+
+        let mut arrived = vec![];
+        let mut flying = vec![];
+        for flight in self.flights.drain(..) {
+            let to = ui.xy_of(flight.to);
+            let from = ui.xy_of(flight.from).or(to);
+            let t = flight.progress(since(self.clock, flight.start));
+            match (from, to) {
+                (Some(from), Some(to)) if t < 1.0 => flying.push((flight, from, to, t)),
+                _ => {
+                    arrived.push(Arrival {
+                                     item: flight.item,
+                                     to: flight.to,
+                                 })
+                }
+            }
+        }
+
+        for &(ref flight, from, to, t) in flying.iter() {
+            if flight.start > now {
+                continue;
+            }
+            let card_id = state.ids.cards[flight.slot.unwrap()];
+            let p = flight.easing.apply(t);
+            let xy = flight.path.point(from, to, p);
+            let scale = flight.scale.0.lerp(&flight.scale.1, p);
+            (*self.widget_closure)(flight.item.clone())
+                .x_y(xy[0], xy[1])
+                .w_h(card_dim[0] * scale, card_dim[1] * scale)
+                .parent(id)
+                .set(card_id, ui);
+        }
+
+        let in_flight = flying.len();
+        self.flights.extend(flying.into_iter().map(|(flight, _, _, _)| flight));
+        Flights {
+            arrived: arrived,
+            in_flight: in_flight,
+        }
+    }
+}
@@ -1,25 +1,62 @@
 use conrod_core::{widget, Positionable, Widget, Sizeable, Rect, image};
 use sprite::{spriteable_rect, Spriteable};
+use clock::{secs, Clock, SYSTEM_CLOCK};
 use std::time::{Duration, Instant};
 
 /// The type upon which we'll implement the `Widget` trait.
 #[derive(WidgetCommon)]
-pub struct FullCycleSprite<H: Spriteable> {
+pub struct FullCycleSprite<'a, H: Spriteable> {
     /// An object that handles some of the dirty work of rendering a GUI. We don't
     /// really have to worry about it.
     #[conrod(common_builder)]
     common: widget::CommonBuilder,
     pub image: image::Id,
     pub sprite: H,
+    pub clock: &'a Clock,
     /// See the Style struct below.
     style: Style,
 }
 
+/// What happens once the last frame has been shown.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PlayMode {
+    /// Start again from the first frame.
+    Loop,
+    /// Play through once, then draw nothing.
+    Once,
+    /// Play forwards, then backwards, and repeat.
+    PingPong,
+    /// Play through once, then keep showing the last frame.
+    HoldLast,
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
 pub struct Style {
-    ///the smaller the value, the slower it gets
-    #[conrod(default = "0.5")]
-    pub frame_rate: Option<f32>,
+    /// How long each frame is shown when the sprite doesn't give its own duration.
+    #[conrod(default = "Duration::from_millis(100)")]
+    pub frame_duration: Option<Duration>,
+    #[conrod(default = "PlayMode::Loop")]
+    pub play_mode: Option<PlayMode>,
+}
+
+/// The event produced by `FullCycleSprite` on every update.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Playback {
+    /// The given frame is being shown.
+    Playing(usize),
+    /// A `Once` or `HoldLast` animation reached its end during this update.
+    Finished,
+    /// A `Once` or `HoldLast` animation ended during an earlier update.
+    Done,
+}
+impl Playback {
+    /// `true` once a one-shot animation has played to the end.
+    pub fn is_finished(&self) -> bool {
+        match *self {
+            Playback::Playing(_) => false,
+            Playback::Finished | Playback::Done => true,
+        }
+    }
 }
 
 widget_ids! {
@@ -31,11 +68,12 @@ widget_ids! {
 /// Represents the unique, cached state for our CardViewPartial widget.
 pub struct State {
     ids: Ids,
-    frame: f32,
+    elapsed: Duration,
     last_update: Instant,
+    finished: bool,
 }
 
-impl<H> FullCycleSprite<H>
+impl<'a, H> FullCycleSprite<'a, H>
     where H: Spriteable
 {
     /// Create a button context to be built upon.
@@ -43,18 +81,25 @@ impl<H> FullCycleSprite<H>
         FullCycleSprite {
             image: image,
             sprite: sprite,
+            clock: &SYSTEM_CLOCK,
             common: widget::CommonBuilder::default(),
             style: Style::default(),
         }
     }
     builder_methods!{
-        pub frame_rate { style.frame_rate = Some(f32) }
+        pub frame_duration { style.frame_duration = Some(Duration) }
+        pub play_mode { style.play_mode = Some(PlayMode) }
+    }
+    /// Read the time from `clock` instead of the system clock.
+    pub fn clock(mut self, clock: &'a Clock) -> Self {
+        self.clock = clock;
+        self
     }
 }
 
 /// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
 /// documentation for more details.
-impl<H> Widget for FullCycleSprite<H>
+impl<'a, H> Widget for FullCycleSprite<'a, H>
     where H: Spriteable
 {
     /// The State struct that we defined above.
@@ -62,15 +107,14 @@ impl<H> Widget for FullCycleSprite<H>
     /// The Style struct that we defined using the `widget_style!` macro.
     type Style = Style;
     /// The event produced by instantiating the widget.
-    ///
-    /// `Some` when clicked, otherwise `None`.
-    type Event = Option<()>;
+    type Event = Playback;
 
     fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
         State {
             ids: Ids::new(id_gen),
-            frame: 0.0,
-            last_update: Instant::now(),
+            elapsed: Duration::new(0, 0),
+            last_update: self.clock.now(),
+            finished: false,
         }
     }
 
@@ -87,32 +131,32 @@ impl<H> Widget for FullCycleSprite<H>
         // necessary primitive graphics widgets.
         //
         let (_, _, w, h) = rect.x_y_w_h();
-        let frame_rate = self.style.frame_rate(ui.theme());
-        let num = self.sprite.num_in_col() * self.sprite.num_in_row();
-        let frame_c = state.frame.clone();
-        let now = Instant::now();
-        let _step = if now.clone().duration_since(state.last_update) < Duration::new(3, 0) {
-            if frame_c <= num as f32 {
-                state.update(|state| {
-                                 state.frame += frame_rate;
-                                 state.last_update = now;
-                             });
-                Some((frame_c.floor() as u16 % num) as f64)
-            } else {
-                state.update(|state| state.frame = 0.0);
-                Some(0.0)
-            }
+        let default_duration = self.style.frame_duration(ui.theme());
+        let play_mode = self.style.play_mode(ui.theme());
+        let durations = (0..self.sprite.num_frames() as usize)
+            .map(|i| self.sprite.frame_duration(i).unwrap_or(default_duration))
+            .collect::<Vec<Duration>>();
+        let now = self.clock.now();
+        let elapsed = if now > state.last_update {
+            state.elapsed + now.duration_since(state.last_update)
         } else {
-            state.update(|state| {
-                             state.frame = 0.0;
-                             state.last_update = now;
-                         });
-            Some(0.0)
+            state.elapsed
+        };
+        state.update(|state| {
+                         state.elapsed = elapsed;
+                         state.last_update = now;
+                     });
+        let (frame, ended) = match frame_at(&durations, elapsed, play_mode) {
+            Some(f) => f,
+            None => return Playback::Done,
         };
+        let was_finished = state.finished;
+        if ended && !was_finished {
+            state.update(|state| state.finished = true);
+        }
 
-        let s = self.sprite;
-        if let Some(_s) = _step {
-            let r = spriteable_rect(s, _s);
+        if !(ended && play_mode == PlayMode::Once) {
+            let r = spriteable_rect(self.sprite, frame as f64);
             widget::Image::new(self.image)
                 .source_rectangle(Rect::from_corners(r.0, r.1))
                 .w_h(w, h)
@@ -120,9 +164,45 @@ impl<H> Widget for FullCycleSprite<H>
                 .parent(id)
                 .graphics_for(id)
                 .set(state.ids.bottle0, ui);
+        }
 
+        match (ended, was_finished) {
+            (false, _) => Playback::Playing(frame),
+            (true, false) => Playback::Finished,
+            (true, true) => Playback::Done,
         }
+    }
+}
 
-        Some(())
+/// The frame to show after `elapsed`, and whether a one-shot mode has played to the end.
+fn frame_at(durations: &[Duration], elapsed: Duration, mode: PlayMode) -> Option<(usize, bool)> {
+    let last = match durations.len() {
+        0 => return None,
+        n => n - 1,
+    };
+    let order = match mode {
+        PlayMode::PingPong => (0..last + 1).chain((1..last).rev()).collect::<Vec<usize>>(),
+        _ => (0..last + 1).collect::<Vec<usize>>(),
+    };
+    let total = order.iter().map(|&i| secs(durations[i])).sum::<f64>();
+    if total <= 0.0 {
+        return Some((0, false));
+    }
+    let mut t = secs(elapsed);
+    match mode {
+        PlayMode::Loop | PlayMode::PingPong => t %= total,
+        PlayMode::Once | PlayMode::HoldLast => {
+            if t >= total {
+                return Some((last, true));
+            }
+        }
+    }
+    for &i in order.iter() {
+        let d = secs(durations[i]);
+        if t < d {
+            return Some((i, false));
+        }
+        t -= d;
     }
+    Some((*order.last().unwrap_or(&0), false))
 }
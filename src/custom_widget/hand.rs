@@ -0,0 +1,238 @@
+use conrod_core::{widget, event, input, Point, Positionable, Rect, Scalar, Sizeable, Widget};
+
+/// A player's hand of cards, overlapping along an arc. The card under the mouse is raised,
+/// enlarged and drawn in front of the others.
+///
+/// Cards are not rotated; the arc only sets where they sit. Every card is graphics for the
+/// hand, so keep the hand's rect big enough to hold the raised card.
+#[derive(WidgetCommon)]
+pub struct Hand<'a, T, W>
+    where T: Clone + 'a,
+          W: Widget
+{
+    /// An object that handles some of the dirty work of rendering a GUI. We don't
+    /// really have to worry about it.
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    values: &'a Vec<T>,
+    widget_closure: Box<Fn(T) -> W>,
+    /// See the Style struct below.
+    style: Style,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
+pub struct Style {
+    #[conrod(default = "[100.0, 140.0]")]
+    pub card_dim: Option<[f64; 2]>,
+    /// The radius of the arc. The larger it is, the flatter the hand; 0 lays the cards in a row.
+    #[conrod(default = "600.0")]
+    pub radius: Option<Scalar>,
+    /// The widest angle, in radians, between the first and last card.
+    #[conrod(default = "0.6")]
+    pub max_spread: Option<f64>,
+    /// How much of a card the next one covers, from 0 to 1. Cards are pushed closer together
+    /// when the hand would be wider than the widget or `max_spread`.
+    #[conrod(default = "0.5")]
+    pub overlap: Option<f64>,
+    /// How far the hovered card is raised.
+    #[conrod(default = "30.0")]
+    pub hover_lift: Option<Scalar>,
+    /// The size of the hovered card as a multiple of `card_dim`.
+    #[conrod(default = "1.2")]
+    pub hover_zoom: Option<f64>,
+}
+
+/// Something that happened to a card of the hand, by its index in the values.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HandEvent {
+    Click(usize),
+    /// The mouse moved onto another card, or off the hand.
+    Hover(Option<usize>),
+    /// The mouse started dragging a card.
+    DragStart(usize),
+}
+
+widget_ids! {
+    struct Ids {
+        cards[],
+    }
+}
+
+/// Represents the unique, cached state for our Hand widget.
+pub struct State {
+    ids: Ids,
+    hovered: Option<usize>,
+    dragging: bool,
+}
+
+impl<'a, T, W> Hand<'a, T, W>
+    where T: Clone + 'a,
+          W: Widget
+{
+    /// Create a button context to be built upon.
+    pub fn new(values: &'a Vec<T>, widget_closure: Box<Fn(T) -> W>) -> Self {
+        Hand {
+            values: values,
+            widget_closure: widget_closure,
+            common: widget::CommonBuilder::default(),
+            style: Style::default(),
+        }
+    }
+    builder_methods!{
+        pub card_dim { style.card_dim = Some([f64; 2]) }
+        pub radius { style.radius = Some(Scalar) }
+        pub max_spread { style.max_spread = Some(f64) }
+        pub overlap { style.overlap = Some(f64) }
+        pub hover_lift { style.hover_lift = Some(Scalar) }
+        pub hover_zoom { style.hover_zoom = Some(f64) }
+    }
+}
+
+/// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
+/// documentation for more details.
+impl<'a, T, W> Widget for Hand<'a, T, W>
+    where T: Clone + 'a,
+          W: Widget
+{
+    /// The State struct that we defined above.
+    type State = State;
+    /// The Style struct that we defined using the `widget_style!` macro.
+    type Style = Style;
+    /// The event produced by instantiating the widget.
+    type Event = Vec<HandEvent>;
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State {
+            ids: Ids::new(id_gen),
+            hovered: None,
+            dragging: false,
+        }
+    }
+
+    fn style(&self) -> Self::Style {
+        self.style.clone()
+    }
+
+    /// Update the state of the button by handling any input that has occurred since the last
+    /// update.
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { id, state, rect, ui, .. } = args;
+        let len = self.values.len();
+        if state.ids.cards.len() < len {
+            let id_gen = &mut ui.widget_id_generator();
+            state.update(|state| state.ids.cards.resize(len, id_gen));
+        }
+        let card_dim = self.style.card_dim(&ui.theme);
+        let lift = self.style.hover_lift(&ui.theme);
+        let zoom = self.style.hover_zoom(&ui.theme);
+        let centres: Vec<Point> = fan(len,
+                                      card_dim[0],
+                                      self.style.radius(&ui.theme),
+                                      self.style.max_spread(&ui.theme),
+                                      self.style.overlap(&ui.theme),
+                                      rect.w())
+                .into_iter()
+                .map(|p| [rect.x() + p[0], rect.y() + p[1]])
+                .collect();
+        let raised = |i: usize| {
+            let c = centres[i];
+            Rect::from_xy_dim([c[0], c[1] + lift], [card_dim[0] * zoom, card_dim[1] * zoom])
+        };
+        // The hovered card is in front, then later cards cover earlier ones.
+        let card_at = |xy: Point, hovered: Option<usize>| {
+            hovered.filter(|&i| i < len && raised(i).is_over(xy)).or_else(|| {
+                (0..len).rev().find(|&i| Rect::from_xy_dim(centres[i], card_dim).is_over(xy))
+            })
+        };
+
+        let mut events = vec![];
+        let mut dragging = state.dragging;
+        // Event positions are relative to the middle of the hand.
+        let abs = |xy: Point| [rect.x() + xy[0], rect.y() + xy[1]];
+        for widget_event in ui.widget_input(id).events() {
+            match widget_event {
+                event::Widget::Click(click) if click.button == input::MouseButton::Left => {
+                    if let Some(i) = card_at(abs(click.xy), state.hovered) {
+                        events.push(HandEvent::Click(i));
+                    }
+                }
+                event::Widget::Drag(drag) if drag.button == input::MouseButton::Left &&
+                                             !dragging => {
+                    dragging = true;
+                    if let Some(i) = card_at(abs(drag.origin), state.hovered) {
+                        events.push(HandEvent::DragStart(i));
+                    }
+                }
+                event::Widget::Release(release) => {
+                    if let event::Button::Mouse(input::MouseButton::Left, _) = release.button {
+                        dragging = false;
+                    }
+                }
+                _ => {}
+            }
+        }
+        let hovered = ui.widget_input(id)
+            .mouse()
+            .and_then(|mouse| card_at(mouse.abs_xy(), state.hovered));
+        if hovered != state.hovered {
+            events.push(HandEvent::Hover(hovered));
+        }
+        if hovered != state.hovered || dragging != state.dragging {
+            state.update(|state| {
+                             state.hovered = hovered;
+                             state.dragging = dragging;
+                         });
+        }
+
+        // Set the hovered card last so it is drawn on top.
+        let order = (0..len).filter(|&i| Some(i) != hovered).chain(hovered);
+        for i in order {
+            let (xy, dim) = if Some(i) == hovered {
+                let r = raised(i);
+                (r.xy(), r.dim())
+            } else {
+                (centres[i], card_dim)
+            };
+            (*self.widget_closure)(self.values[i].clone())
+                .x_y(xy[0], xy[1])
+                .w_h(dim[0], dim[1])
+                .parent(id)
+                .graphics_for(id)
+                .set(state.ids.cards[i], ui);
+        }
+        events
+    }
+}
+
+/// The middle of each of `len` cards relative to the middle of the hand, along an arc of
+/// `radius` whose top is at the middle.
+fn fan(len: usize,
+       card_w: Scalar,
+       radius: Scalar,
+       max_spread: f64,
+       overlap: f64,
+       max_w: Scalar)
+       -> Vec<Point> {
+    if len == 0 {
+        return vec![];
+    }
+    let gaps = (len - 1) as f64;
+    let mut step = card_w * (1.0 - overlap);
+    if len > 1 {
+        step = step.min((max_w - card_w).max(0.0) / gaps);
+    }
+    let offset = |i: usize| i as f64 - gaps / 2.0;
+    if radius <= 0.0 {
+        return (0..len).map(|i| [offset(i) * step, 0.0]).collect();
+    }
+    let mut angle = step / radius;
+    if len > 1 {
+        angle = angle.min(max_spread / gaps);
+    }
+    (0..len)
+        .map(|i| {
+                 let a = offset(i) * angle;
+                 [radius * a.sin(), radius * (a.cos() - 1.0)]
+             })
+        .collect()
+}
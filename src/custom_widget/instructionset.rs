@@ -2,6 +2,10 @@ use conrod_core::{self, widget, Positionable, Widget, Labelable, Colorable, Size
 use conrod_core::widget::{Rectangle, Oval};
 use conrod_core::widget::primitive::shape::oval::Full;
 use conrod_core::widget::button::{Button, Flat};
+use custom_widget::rich_text::{RichText, IconMap};
+use locale::{tr_or, Locale};
+use clock::{frames_since, Clock, SYSTEM_CLOCK};
+use std::time::Instant;
 pub trait Instructable<'a> {
     fn label(&self) -> &'a str;
     fn rect(&self, [f64; 2]) -> Rectangle;
@@ -21,6 +25,11 @@ pub struct InstructionSet<'a, I>
     pub instructions: &'a Vec<I>, //str,[l,t,w,h of rect],Some([l,t,w,h of oval])
     pub instruction_cache: &'a mut usize,
     pub next: &'a str,
+    /// When set, instruction labels are rich text markup and `{name}` draws an icon from the map.
+    pub icons: Option<&'a IconMap>,
+    /// When set, instruction labels and `next` are message keys looked up in the locale.
+    pub locale: Option<&'a Locale>,
+    pub clock: &'a Clock,
     /// See the Style struct below.
     style: Style,
 }
@@ -48,6 +57,7 @@ widget_ids! {
         oval,
         frame,
         instruction,
+        rich_instruction,
         next,
     }
 }
@@ -55,7 +65,7 @@ widget_ids! {
 /// Represents the unique, cached state for our InstructionSet widget.
 pub struct State {
     ids: Ids,
-    frame: usize,
+    start: Instant,
 }
 
 impl<'a, I> InstructionSet<'a, I>
@@ -67,6 +77,9 @@ impl<'a, I> InstructionSet<'a, I>
             instructions: instructions,
             instruction_cache: instruction_cache,
             next: next,
+            icons: None,
+            locale: None,
+            clock: &SYSTEM_CLOCK,
             common: widget::CommonBuilder::default(),
             style: Style::default(),
         }
@@ -81,6 +94,21 @@ impl<'a, I> InstructionSet<'a, I>
         self.style.parent_id = Some(Some(parent_id));
         self
     }
+    /// Render instruction labels as rich text markup with icons from `icons`.
+    pub fn icons(mut self, icons: &'a IconMap) -> Self {
+        self.icons = Some(icons);
+        self
+    }
+    /// Treat instruction labels and `next` as message keys of `locale`.
+    pub fn locale(mut self, locale: &'a Locale) -> Self {
+        self.locale = Some(locale);
+        self
+    }
+    /// Read the time from `clock` instead of the system clock.
+    pub fn clock(mut self, clock: &'a Clock) -> Self {
+        self.clock = clock;
+        self
+    }
     builder_methods!{
         pub button_color { style.button_color = Some(conrod_core::Color) }
         pub label_color{style.label_color = Some(conrod_core::Color)}
@@ -105,7 +133,7 @@ impl<'a, I> Widget for InstructionSet<'a, I>
     fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
         State {
             ids: Ids::new(id_gen),
-            frame: 0,
+            start: self.clock.now(),
         }
     }
 
@@ -127,7 +155,8 @@ impl<'a, I> Widget for InstructionSet<'a, I>
         };
 
         if let Some(_inst) = self.instructions.get(self.instruction_cache.clone()) {
-            let (_label, _rect, _button, _oval_one, _oval_two) = (_inst.label(),
+            let (_label, _rect, _button, _oval_one, _oval_two) = (tr_or(self.locale,
+                                                                        _inst.label()),
                                                                   _inst.rect([w, h]),
                                                                   _inst.button([w, h]),
                                                                   _inst.oval_one([w, h]),
@@ -135,17 +164,29 @@ impl<'a, I> Widget for InstructionSet<'a, I>
             _rect.set(state.ids.frame, ui);
             let (_rx, _ry, _rw, _rh) = ui.rect_of(state.ids.frame).unwrap().x_y_w_h();
             let font_id = style.label_font_id(&ui.theme).or(ui.fonts.ids().next());
-            widget::TextEdit::new(_label)
-                .font_id(font_id.unwrap())
-                .color(style.label_color(&ui.theme))
-                .font_size(style.label_font_size(&ui.theme))
-                .padded_w_of(state.ids.frame, 0.1 * _rw)
-                .padded_h_of(state.ids.frame, 0.1 * _rh)
-                .top_left_with_margins_on(state.ids.frame, 0.1 * _rh, 0.1 * _rw)
-                .set(state.ids.instruction, ui);
+            if let Some(icons) = self.icons {
+                RichText::new(_label)
+                    .icons(icons)
+                    .font_id(font_id.unwrap())
+                    .color(style.label_color(&ui.theme))
+                    .font_size(style.label_font_size(&ui.theme))
+                    .padded_w_of(state.ids.frame, 0.1 * _rw)
+                    .padded_h_of(state.ids.frame, 0.1 * _rh)
+                    .top_left_with_margins_on(state.ids.frame, 0.1 * _rh, 0.1 * _rw)
+                    .set(state.ids.rich_instruction, ui);
+            } else {
+                widget::TextEdit::new(_label)
+                    .font_id(font_id.unwrap())
+                    .color(style.label_color(&ui.theme))
+                    .font_size(style.label_font_size(&ui.theme))
+                    .padded_w_of(state.ids.frame, 0.1 * _rw)
+                    .padded_h_of(state.ids.frame, 0.1 * _rh)
+                    .top_left_with_margins_on(state.ids.frame, 0.1 * _rh, 0.1 * _rw)
+                    .set(state.ids.instruction, ui);
+            }
 
             let j = _button.color(style.button_color(&ui.theme))
-                .label(self.next)
+                .label(tr_or(self.locale, self.next))
                 .parent(state.ids.frame)
                 .set(state.ids.next, ui);
             for _ in j {
@@ -157,12 +198,11 @@ impl<'a, I> Widget for InstructionSet<'a, I>
 
             }
             if let (Some(_oval_one), Some(_oval_two)) = (_oval_one, _oval_two) {
-                if state.frame == 0 {
+                // Alternate between the two ovals every frame at `clock::NOMINAL_FPS`.
+                if frames_since(self.clock, state.start) as u64 % 2 == 0 {
                     _oval_one.set(state.ids.oval, ui);
-                    state.update(|state| state.frame += 1);
                 } else {
                     _oval_two.set(state.ids.oval, ui);
-                    state.update(|state| state.frame = 0);
                 }
             }
 
@@ -170,3 +210,8 @@ impl<'a, I> Widget for InstructionSet<'a, I>
         print
     }
 }
+
+/// The event of an `InstructionSet`, which is `true` while there are instructions left, wrapped
+/// for `Timeline::report`. Its step finishes once the last instruction has been read.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct InstructionsOpen(pub bool);
@@ -1,4 +1,5 @@
 use conrod_core::{self, widget, Colorable, Labelable, Positionable, Widget, image, Sizeable, Rect};
+use custom_widget::tooltip::Tooltips;
 
 /// The type upon which we'll implement the `Widget` trait.
 #[derive(WidgetCommon)]
@@ -15,6 +16,8 @@ pub struct ListSelect<'a> {
     /// Whether the button is currently enabled, i.e. whether it responds to
     /// user input.
     enabled: bool,
+    /// Where to ask for tooltips, and the tooltip of each symbol.
+    tooltips: Option<(&'a Tooltips, &'a [&'a str])>,
 }
 
 #[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
@@ -63,6 +66,7 @@ impl<'a> ListSelect<'a> {
             style: Style::default(),
             maybe_label: None,
             enabled: true,
+            tooltips: None,
         }
     }
 
@@ -80,6 +84,11 @@ impl<'a> ListSelect<'a> {
         self.enabled = flag;
         self
     }
+    /// Show `texts[i]` as a tooltip while symbol `i` is hovered. Empty texts show nothing.
+    pub fn tooltip(mut self, tooltips: &'a Tooltips, texts: &'a [&'a str]) -> Self {
+        self.tooltips = Some((tooltips, texts));
+        self
+    }
 }
 
 /// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
@@ -166,7 +175,12 @@ impl<'a> Widget for ListSelect<'a> {
                 }
                 _ => {}
             }
-
+            if let Some((tooltips, texts)) = self.tooltips {
+                match texts.get(counter) {
+                    Some(text) if !text.is_empty() => tooltips.request_if_hovered(ui, sym, text),
+                    _ => {}
+                }
+            }
         }
 
         // Now we'll instantiate our label using the **Text** widget.
@@ -20,3 +20,16 @@ pub mod arrange_list;
 pub mod player_info;
 pub mod notification;
 pub mod progress_bar;
+pub mod nine_slice;
+pub mod rich_text;
+pub mod card_flip;
+pub mod flying_card;
+pub mod card_face;
+pub mod hand;
+pub mod pile;
+pub mod zoom;
+pub mod tooltip;
+pub mod card_collection;
+pub mod deck_builder;
+pub mod board;
+pub mod targeting_arrow;
@@ -0,0 +1,188 @@
+use conrod_core::{widget, Positionable, Widget, Sizeable, Rect, Range, Scalar, image};
+use sprite::{spriteable_rect, Spriteable};
+
+/// How the edges and centre of a nine-slice image fill the space between the corners.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum EdgeMode {
+    Stretch,
+    Tile,
+}
+
+/// The source image of a nine-slice: an image, the part of it to use and the size of the border.
+///
+/// This is `Copy` so it can be stored by other widget builders such as `animated_canvas::Canvas`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NineSliceImage {
+    pub image_id: image::Id,
+    /// The part of the image holding the frame, in the same coordinates as `spriteable_rect`.
+    pub src_rect: Rect,
+    /// Border thickness in source pixels: left, right, top, bottom (the order of `SpriteInfo::pad`).
+    pub insets: (f64, f64, f64, f64),
+    pub edge_mode: EdgeMode,
+}
+impl NineSliceImage {
+    pub fn new(image_id: image::Id, src_rect: Rect, insets: (f64, f64, f64, f64)) -> Self {
+        NineSliceImage {
+            image_id: image_id,
+            src_rect: src_rect,
+            insets: insets,
+            edge_mode: EdgeMode::Stretch,
+        }
+    }
+    /// Use frame `index` of a sprite sheet as the source.
+    pub fn from_sprite<H: Spriteable>(image_id: image::Id,
+                                      sprite: H,
+                                      index: f64,
+                                      insets: (f64, f64, f64, f64))
+                                      -> Self {
+        let r = spriteable_rect(sprite, index);
+        NineSliceImage::new(image_id, Rect::from_corners(r.0, r.1), insets)
+    }
+    /// Repeat the edges and centre instead of stretching them.
+    pub fn tile(mut self) -> Self {
+        self.edge_mode = EdgeMode::Tile;
+        self
+    }
+}
+
+/// Draws an image as nine pieces so that its corners keep their size however it is scaled.
+#[derive(WidgetCommon)]
+pub struct NineSlice {
+    /// An object that handles some of the dirty work of rendering a GUI. We don't
+    /// really have to worry about it.
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    pub image: NineSliceImage,
+    /// See the Style struct below.
+    style: Style,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
+pub struct Style {
+    /// On-screen size of one source pixel of the border.
+    #[conrod(default = "1.0")]
+    pub border_scale: Option<f64>,
+}
+
+widget_ids! {
+    struct Ids {
+        pieces[],
+    }
+}
+
+/// Represents the unique, cached state for our NineSlice widget.
+pub struct State {
+    ids: Ids,
+}
+
+impl NineSlice {
+    /// Create a button context to be built upon.
+    pub fn new(image: NineSliceImage) -> Self {
+        NineSlice {
+            image: image,
+            common: widget::CommonBuilder::default(),
+            style: Style::default(),
+        }
+    }
+    builder_methods!{
+        pub border_scale { style.border_scale = Some(f64) }
+    }
+}
+
+/// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
+/// documentation for more details.
+impl Widget for NineSlice {
+    /// The State struct that we defined above.
+    type State = State;
+    /// The Style struct that we defined using the `widget_style!` macro.
+    type Style = Style;
+    /// The event produced by instantiating the widget.
+    type Event = ();
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State { ids: Ids::new(id_gen) }
+    }
+
+    fn style(&self) -> Self::Style {
+        self.style.clone()
+    }
+
+    /// Update the state of the button by handling any input that has occurred since the last
+    /// update.
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { id, state, rect, ui, .. } = args;
+        let scale = self.style.border_scale(ui.theme());
+        let pieces = slices(&self.image, rect, scale);
+        if state.ids.pieces.len() < pieces.len() {
+            let id_gen = &mut ui.widget_id_generator();
+            state.update(|state| state.ids.pieces.resize(pieces.len(), id_gen));
+        }
+        for (&(src, dest), &piece_id) in pieces.iter().zip(state.ids.pieces.iter()) {
+            widget::Image::new(self.image.image_id)
+                .source_rectangle(src)
+                .x_y(dest.x(), dest.y())
+                .w_h(dest.w(), dest.h())
+                .parent(id)
+                .graphics_for(id)
+                .set(piece_id, ui);
+        }
+    }
+}
+
+/// Pairs of (source rect, destination rect) covering `dest` with the nine pieces of `image`.
+fn slices(image: &NineSliceImage, dest: Rect, scale: Scalar) -> Vec<(Rect, Rect)> {
+    let src = image.src_rect;
+    let (l, r, t, b) = image.insets;
+    // Shrink the border when the widget is too small to hold both opposite corners.
+    let kx = (dest.w() / ((l + r) * scale)).min(1.0);
+    let ky = (dest.h() / ((t + b) * scale)).min(1.0);
+    let src_cols = [src.left(), src.left() + l, src.right() - r, src.right()];
+    let src_rows = [src.bottom(), src.bottom() + b, src.top() - t, src.top()];
+    let dest_cols = [dest.left(),
+                     dest.left() + l * scale * kx,
+                     dest.right() - r * scale * kx,
+                     dest.right()];
+    let dest_rows = [dest.bottom(),
+                     dest.bottom() + b * scale * ky,
+                     dest.top() - t * scale * ky,
+                     dest.top()];
+    let mut pieces = vec![];
+    for row in 0..3 {
+        for col in 0..3 {
+            let s_x = Range::new(src_cols[col], src_cols[col + 1]);
+            let s_y = Range::new(src_rows[row], src_rows[row + 1]);
+            let d_x = Range::new(dest_cols[col], dest_cols[col + 1]);
+            let d_y = Range::new(dest_rows[row], dest_rows[row + 1]);
+            if s_x.magnitude() <= 0.0 || s_y.magnitude() <= 0.0 || d_x.magnitude() <= 0.0 ||
+               d_y.magnitude() <= 0.0 {
+                continue;
+            }
+            let tile = image.edge_mode == EdgeMode::Tile;
+            let xs = if tile && col == 1 { tiles(s_x, d_x, scale) } else { vec![(s_x, d_x)] };
+            let ys = if tile && row == 1 { tiles(s_y, d_y, scale) } else { vec![(s_y, d_y)] };
+            for &(s_x, d_x) in xs.iter() {
+                for &(s_y, d_y) in ys.iter() {
+                    pieces.push((Rect { x: s_x, y: s_y }, Rect { x: d_x, y: d_y }));
+                }
+            }
+        }
+    }
+    pieces
+}
+
+/// Repeat `src` along `dest` at `scale`, cropping the last copy to fit.
+fn tiles(src: Range, dest: Range, scale: Scalar) -> Vec<(Range, Range)> {
+    let step = src.len() * scale;
+    if step <= 0.0 {
+        return vec![(src, dest)];
+    }
+    let mut out = vec![];
+    let mut start = dest.start;
+    while start < dest.end - 0.5 {
+        let len = step.min(dest.end - start);
+        let src_len = len / scale;
+        out.push((Range::new(src.start, src.start + src_len), Range::new(start, start + len)));
+        start += len;
+    }
+    out
+}
@@ -1,5 +1,8 @@
 use conrod_core::{widget, Positionable, Widget, Color, Colorable, Sizeable};
-use text::get_font_size_hn;
+use text::{get_font_size_hn, fit_font_size_wrapped, font_of};
+use custom_widget::rich_text::{self, RichText, IconMap};
+use clock::{since, Clock, SYSTEM_CLOCK};
+use std::cmp::min;
 use std::time::Duration;
 use std::time::Instant;
 /// The type upon which we'll implement the `Widget` trait.
@@ -11,6 +14,9 @@ pub struct Notification<'a> {
     common: widget::CommonBuilder,
     pub text: &'a str,
     pub start: Instant,
+    /// When set, `text` is rich text markup and `{name}` draws an icon from the map.
+    pub icons: Option<&'a IconMap>,
+    pub clock: &'a Clock,
     /// See the Style struct below.
     style: Style,
 }
@@ -31,7 +37,8 @@ pub struct Style {
 widget_ids! {
     struct Ids {
         rect,
-        text
+        text,
+        rich_text,
     }
 }
 
@@ -46,10 +53,23 @@ impl<'a> Notification<'a> {
         Notification {
             text: text,
             start: start,
+            icons: None,
+            clock: &SYSTEM_CLOCK,
             common: widget::CommonBuilder::default(),
             style: Style::default(),
         }
     }
+    /// Render `text` as rich text markup with icons from `icons`.
+    pub fn icons(mut self, icons: &'a IconMap) -> Self {
+        self.icons = Some(icons);
+        self
+    }
+    /// Read the time from `clock` instead of the system clock. `start` should come from the
+    /// same clock.
+    pub fn clock(mut self, clock: &'a Clock) -> Self {
+        self.clock = clock;
+        self
+    }
     builder_methods!{
      pub num_lines { style.num_lines = Some(f64) }
      pub duration{style.duration = Some(Duration)}
@@ -84,22 +104,41 @@ impl<'a> Widget for Notification<'a> {
         // Finally, we'll describe how we want our widget drawn by simply instantiating the
         // necessary primitive graphics widgets.
         //
-        if self.start.elapsed() < self.style.duration(&ui.theme) {
+        if since(self.clock, self.start) < self.style.duration(&ui.theme) {
             let (_, _, w, h) = rect.x_y_w_h();
             let color = self.style.color(&ui.theme);
             widget::Rectangle::fill_with([w, h], color)
                 .middle_of(id)
                 .parent(id)
                 .set(state.ids.rect, ui);
-            let n = self.style.num_lines(&ui.theme);
-            let font_size = get_font_size_hn(h, n);
-            widget::Text::new(self.text)
-                .font_size(font_size)
-                .middle_of(id)
-                .wh_of(id)
-                .parent(id)
-                .color(color.plain_contrast())
-                .set(state.ids.text, ui);
+            // `num_lines` caps the size so that short messages aren't blown up to fill the rect.
+            let max_font_size = get_font_size_hn(h, self.style.num_lines(&ui.theme));
+            let plain = match self.icons {
+                Some(_) => rich_text::plain_text(self.text),
+                None => self.text.to_owned(),
+            };
+            let font_size = match font_of(ui, None) {
+                Some(font) => min(max_font_size, fit_font_size_wrapped(font, &plain, w, h, 1.0)),
+                None => max_font_size,
+            };
+            if let Some(icons) = self.icons {
+                RichText::new(self.text)
+                    .icons(icons)
+                    .font_size(font_size)
+                    .middle_of(id)
+                    .wh_of(id)
+                    .parent(id)
+                    .color(color.plain_contrast())
+                    .set(state.ids.rich_text, ui);
+            } else {
+                widget::Text::new(self.text)
+                    .font_size(font_size)
+                    .middle_of(id)
+                    .wh_of(id)
+                    .parent(id)
+                    .color(color.plain_contrast())
+                    .set(state.ids.text, ui);
+            }
         }
 
     }
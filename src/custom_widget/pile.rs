@@ -0,0 +1,277 @@
+use conrod_core::{widget, color, text, Color, Colorable, FontSize, Positionable, Scalar, Sizeable,
+                  Widget};
+use conrod_core::widget::primitive::image::Image;
+use custom_widget::image_panels::list_select;
+
+/// A deck or discard pile: a stack of card backs that gets thicker as it holds more cards, with
+/// a badge showing the count. A discard pile shows its top card face up.
+///
+/// In inspect mode the pile also opens a scrollable grid of all its faces, top card first.
+#[derive(WidgetCommon)]
+pub struct Pile<'a, T, W>
+    where T: Clone + 'a,
+          W: Widget
+{
+    /// An object that handles some of the dirty work of rendering a GUI. We don't
+    /// really have to worry about it.
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    /// The cards in the pile, bottom card first.
+    values: &'a Vec<T>,
+    widget_closure: Box<Fn(T) -> W>,
+    pub back_card: Image,
+    /// Show the top card face up.
+    pub face_up: bool,
+    /// Show the grid of faces.
+    pub inspect: bool,
+    /// See the Style struct below.
+    style: Style,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
+pub struct Style {
+    /// How far the top of the stack moves up and right per card.
+    #[conrod(default = "0.5")]
+    pub depth_step: Option<Scalar>,
+    /// The most the stack can rise, however many cards it holds.
+    #[conrod(default = "12.0")]
+    pub max_depth: Option<Scalar>,
+    /// The most backs drawn to show the depth.
+    #[conrod(default = "5")]
+    pub layers: Option<usize>,
+    #[conrod(default = "theme.shape_color")]
+    pub badge_color: Option<Color>,
+    #[conrod(default = "theme.label_color")]
+    pub label_color: Option<Color>,
+    #[conrod(default = "theme.font_size_small")]
+    pub label_font_size: Option<FontSize>,
+    /// Specify a unique font for the label.
+    #[conrod(default = "theme.font_id")]
+    pub label_font_id: Option<Option<text::font::Id>>,
+    /// The size of the inspect grid, which is placed in the middle of the window.
+    #[conrod(default = "[600.0, 400.0]")]
+    pub inspect_dim: Option<[f64; 2]>,
+    /// The size of each face in the inspect grid.
+    #[conrod(default = "[100.0, 140.0]")]
+    pub inspect_card_dim: Option<[f64; 2]>,
+}
+
+/// The event produced by `Pile`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PileEvent {
+    /// The pile was clicked, e.g. to draw its top card.
+    Draw,
+    /// A face in the inspect grid was clicked, by its index in the values.
+    Inspect(usize),
+}
+
+widget_ids! {
+    struct Ids {
+        empty,
+        layers[],
+        top,
+        badge,
+        count,
+        grid_back,
+        grid,
+        faces[],
+    }
+}
+
+/// Represents the unique, cached state for our Pile widget.
+pub struct State {
+    ids: Ids,
+}
+
+impl<'a, T, W> Pile<'a, T, W>
+    where T: Clone + 'a,
+          W: Widget
+{
+    /// Create a button context to be built upon.
+    pub fn new(values: &'a Vec<T>, widget_closure: Box<Fn(T) -> W>, back_card: Image) -> Self {
+        Pile {
+            values: values,
+            widget_closure: widget_closure,
+            back_card: back_card,
+            face_up: false,
+            inspect: false,
+            common: widget::CommonBuilder::default(),
+            style: Style::default(),
+        }
+    }
+    /// Show the top card face up, as for a discard pile.
+    pub fn face_up(mut self, face_up: bool) -> Self {
+        self.face_up = face_up;
+        self
+    }
+    /// Open the grid of faces.
+    pub fn inspect(mut self, inspect: bool) -> Self {
+        self.inspect = inspect;
+        self
+    }
+    /// Specify the font used for displaying the count.
+    pub fn label_font_id(mut self, font_id: text::font::Id) -> Self {
+        self.style.label_font_id = Some(Some(font_id));
+        self
+    }
+    builder_methods!{
+        pub depth_step { style.depth_step = Some(Scalar) }
+        pub max_depth { style.max_depth = Some(Scalar) }
+        pub layers { style.layers = Some(usize) }
+        pub badge_color { style.badge_color = Some(Color) }
+        pub label_color { style.label_color = Some(Color) }
+        pub label_font_size { style.label_font_size = Some(FontSize) }
+        pub inspect_dim { style.inspect_dim = Some([f64; 2]) }
+        pub inspect_card_dim { style.inspect_card_dim = Some([f64; 2]) }
+    }
+}
+
+/// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
+/// documentation for more details.
+impl<'a, T, W> Widget for Pile<'a, T, W>
+    where T: Clone + 'a,
+          W: Widget
+{
+    /// The State struct that we defined above.
+    type State = State;
+    /// The Style struct that we defined using the `widget_style!` macro.
+    type Style = Style;
+    /// The event produced by instantiating the widget.
+    type Event = Option<PileEvent>;
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State { ids: Ids::new(id_gen) }
+    }
+
+    fn style(&self) -> Self::Style {
+        self.style.clone()
+    }
+
+    /// Update the state of the button by handling any input that has occurred since the last
+    /// update.
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { id, state, rect, ui, .. } = args;
+        let count = self.values.len();
+        let layers = count.min(self.style.layers(&ui.theme).max(1));
+        if state.ids.layers.len() < layers {
+            let id_gen = &mut ui.widget_id_generator();
+            state.update(|state| state.ids.layers.resize(layers, id_gen));
+        }
+        let mut event = {
+            let input = ui.widget_input(id);
+            if count > 0 && input.clicks().left().count() + input.taps().count() > 0 {
+                Some(PileEvent::Draw)
+            } else {
+                None
+            }
+        };
+
+        // The stack rises up and to the right as it grows.
+        let depth = (count as Scalar * self.style.depth_step(&ui.theme))
+            .min(self.style.max_depth(&ui.theme));
+        let card_w = (rect.w() - depth).max(0.0);
+        let card_h = (rect.h() - depth).max(0.0);
+        if count == 0 {
+            widget::Rectangle::outline([card_w, card_h])
+                .color(self.style.badge_color(&ui.theme))
+                .bottom_left_of(id)
+                .parent(id)
+                .graphics_for(id)
+                .set(state.ids.empty, ui);
+        }
+        for l in 0..layers {
+            let offset = if layers > 1 {
+                depth * l as Scalar / (layers - 1) as Scalar
+            } else {
+                0.0
+            };
+            let x = rect.left() + card_w / 2.0 + offset;
+            let y = rect.bottom() + card_h / 2.0 + offset;
+            if l + 1 == layers && self.face_up {
+                (*self.widget_closure)(self.values[count - 1].clone())
+                    .w_h(card_w, card_h)
+                    .x_y(x, y)
+                    .parent(id)
+                    .graphics_for(id)
+                    .set(state.ids.top, ui);
+            } else {
+                self.back_card
+                    .w_h(card_w, card_h)
+                    .x_y(x, y)
+                    .parent(id)
+                    .graphics_for(id)
+                    .set(state.ids.layers[l], ui);
+            }
+        }
+
+        let font_size = self.style.label_font_size(&ui.theme);
+        let badge_r = font_size as Scalar;
+        widget::Circle::fill(badge_r)
+            .color(self.style.badge_color(&ui.theme))
+            .top_right_with_margins_on(id, -badge_r * 0.5, -badge_r * 0.5)
+            .parent(id)
+            .graphics_for(id)
+            .set(state.ids.badge, ui);
+        widget::Text::new(&count.to_string())
+            .font_size(font_size)
+            .color(self.style.label_color(&ui.theme))
+            .and_then(self.style.label_font_id(&ui.theme), widget::Text::font_id)
+            .middle_of(state.ids.badge)
+            .parent(id)
+            .graphics_for(id)
+            .set(state.ids.count, ui);
+
+        if !self.inspect {
+            return event;
+        }
+        if state.ids.faces.len() < count {
+            let id_gen = &mut ui.widget_id_generator();
+            state.update(|state| state.ids.faces.resize(count, id_gen));
+        }
+        let inspect_dim = self.style.inspect_dim(&ui.theme);
+        let face_dim = self.style.inspect_card_dim(&ui.theme);
+        let cols = ((inspect_dim[0] / face_dim[0]).floor() as usize).max(1);
+        let rows = (count + cols - 1) / cols;
+        widget::Rectangle::fill_with(inspect_dim, color::BLACK.alpha(0.8))
+            .middle_of(ui.window)
+            .parent(id)
+            .set(state.ids.grid_back, ui);
+        let (mut events, scrollbar) = list_select::ListSelect::single(rows)
+            .flow_down()
+            .item_size(face_dim[1])
+            .scrollbar_next_to()
+            .wh_of(state.ids.grid_back)
+            .middle_of(state.ids.grid_back)
+            .parent(id)
+            .set(state.ids.grid, ui);
+        while let Some(list_event) = events.next(ui, |_| false) {
+            if let list_select::Event::Item(item) = list_event {
+                let (row, row_id) = (item.i, item.widget_id);
+                item.set(widget::Rectangle::fill_with([inspect_dim[0], face_dim[1]],
+                                                      color::TRANSPARENT),
+                         ui);
+                // Top card first.
+                for col in 0..cols {
+                    let k = row * cols + col;
+                    if k >= count {
+                        break;
+                    }
+                    let i = count - 1 - k;
+                    let face_id = state.ids.faces[k];
+                    (*self.widget_closure)(self.values[i].clone())
+                        .w_h(face_dim[0], face_dim[1])
+                        .top_left_with_margins_on(row_id, 0.0, col as Scalar * face_dim[0])
+                        .parent(row_id)
+                        .set(face_id, ui);
+                    if ui.widget_input(face_id).clicks().left().count() > 0 {
+                        event = Some(PileEvent::Inspect(i));
+                    }
+                }
+            }
+        }
+        if let Some(s) = scrollbar {
+            s.set(ui)
+        }
+        event
+    }
+}
@@ -1,12 +1,13 @@
 use conrod_core::{widget, Positionable, Widget, Sizeable, text, Color, Colorable, Scalar};
 use conrod_core::widget::primitive::image::Image;
 use conrod_core::widget::Rectangle;
-use text::get_font_size_wh;
+use custom_widget::tooltip::Tooltips;
+use text::{get_font_size_wh, fit_font_size, font_of};
 #[derive(Clone)]
 pub struct IconStruct(pub Image, pub String, pub String);
 /// The type upon which we'll implement the `Widget` trait.
 #[derive(WidgetCommon)]
-pub struct Icon {
+pub struct Icon<'a> {
     /// An object that handles some of the dirty work of rendering a GUI. We don't
     /// really have to worry about it.
     #[conrod(common_builder)]
@@ -15,6 +16,8 @@ pub struct Icon {
     /// See the Style struct below.
     style: Style,
     pub bordered: bool,
+    /// Where to ask for the description to be shown while the icon is hovered.
+    pub tooltips: Option<&'a Tooltips>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, WidgetStyle)]
@@ -46,7 +49,7 @@ widget_ids! {
 pub struct State {
     ids: Ids,
 }
-impl Icon {
+impl<'a> Icon<'a> {
     /// Create a button context to be built upon.
     pub fn new(icon: IconStruct) -> Self {
         Icon {
@@ -54,6 +57,7 @@ impl Icon {
             common: widget::CommonBuilder::default(),
             style: Style::default(),
             bordered: false,
+            tooltips: None,
         }
     }
     builder_methods!{
@@ -65,6 +69,11 @@ impl Icon {
         self.bordered = true;
         self
     }
+    /// Show the icon's description as a tooltip while it is hovered.
+    pub fn tooltip(mut self, tooltips: &'a Tooltips) -> Self {
+        self.tooltips = Some(tooltips);
+        self
+    }
     /// Specify the font used for displaying the label.
     pub fn label_font_id(mut self, font_id: text::font::Id) -> Self {
         self.style.label_font_id = Some(Some(font_id));
@@ -74,7 +83,7 @@ impl Icon {
 
 /// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
 /// documentation for more details.
-impl Widget for Icon {
+impl<'a> Widget for Icon<'a> {
     /// The State struct that we defined above.
     type State = State;
     /// The Style struct that we defined using the `widget_style!` macro.
@@ -127,8 +136,11 @@ impl Widget for Icon {
             .parent(id)
             .graphics_for(id)
             .set(state.ids.image, ui);
-        let fontsize = get_font_size_wh(h * 1.2, h * 1.2, &self.icon.1);
-        // let font_id = self.style.label_font_id(&ui.theme).or(ui.fonts.ids().next());
+        let font_id = self.style.label_font_id(&ui.theme);
+        let fontsize = match font_of(ui, font_id) {
+            Some(font) => fit_font_size(font, &self.icon.1, h, h),
+            None => get_font_size_wh(h * 1.2, h * 1.2, &self.icon.1),
+        };
         widget::Text::new(&self.icon.1)
             .w_h(h, h)
             .font_size(fontsize)
@@ -136,9 +148,14 @@ impl Widget for Icon {
             .parent(id)
             .graphics_for(id)
             .left_justify()
-            //.and_then(font_id, widget::Text::font_id)
+            .and_then(font_id, widget::Text::font_id)
             .color(self.style.label_color(&ui.theme))
             .set(state.ids.label, ui);
+        if let Some(tooltips) = self.tooltips {
+            if !self.icon.2.is_empty() {
+                tooltips.request_if_hovered(ui, id, &self.icon.2);
+            }
+        }
 
     }
 }
@@ -1,6 +1,7 @@
 use conrod_core::{widget, Color, Colorable, Positionable, UiCell, Widget, Sizeable, Rect, text};
 use custom_widget::player_info::item::{Icon, IconStruct};
-use text::get_font_size_wh;
+use custom_widget::tooltip::Tooltips;
+use text::{get_font_size_wh, fit_font_size, font_of};
 //Player_info list all player's item, at the end, there is some arrow animation that opens another overlay
 
 /// The type upon which we'll implement the `Widget` trait.
@@ -15,6 +16,8 @@ pub struct List<'a> {
     style: Style,
     pub overlay: &'a mut bool,
     pub maybe_label: Option<&'a str>,
+    /// Where the icons ask for their descriptions to be shown while hovered.
+    pub tooltips: Option<&'a Tooltips>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, WidgetStyle)]
@@ -42,7 +45,6 @@ widget_ids! {
 /// Represents the unique, cached state for our widget.
 pub struct State {
     ids: Ids,
-    frame: u16,
     selected: Option<usize>,
     selected_id: Option<widget::Id>,
     selected_xy: Option<[f64; 2]>,
@@ -57,6 +59,7 @@ impl<'a> List<'a> {
             overlay: overlay,
             style: Style::default(),
             maybe_label: None,
+            tooltips: None,
         }
     }
     builder_methods!{
@@ -68,6 +71,11 @@ impl<'a> List<'a> {
         self.style.label_font_id = Some(Some(font_id));
         self
     }
+    /// Show each icon's description as a tooltip while it is hovered.
+    pub fn tooltip(mut self, tooltips: &'a Tooltips) -> Self {
+        self.tooltips = Some(tooltips);
+        self
+    }
 }
 
 /// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
@@ -85,7 +93,6 @@ impl<'a> Widget for List<'a> {
     fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
         State {
             ids: Ids::new(id_gen),
-            frame: 0,
             selected: None,
             selected_id: None,
             selected_xy: None,
@@ -110,13 +117,16 @@ impl<'a> Widget for List<'a> {
         };
 
         rectangle_fill(id, state.ids.rect, rect, rect_c, ui);
-        //  let font_id = self.style.label_font_id(&ui.theme).or(ui.fonts.ids().next());
+        let font_id = self.style.label_font_id(&ui.theme);
         if let Some(_a) = self.maybe_label {
-            let fontsize = get_font_size_wh(_dim[0] * 0.5, _dim[1], _a);
+            let fontsize = match font_of(ui, font_id) {
+                Some(font) => fit_font_size(font, _a, _dim[0] * 0.3, _dim[1] * 0.9),
+                None => get_font_size_wh(_dim[0] * 0.5, _dim[1], _a),
+            };
             widget::Text::new(_a)
                 .top_left_with_margins_on(id, _dim[1] * 0.1, 0.0)
                 .font_size(fontsize)
-               // .and_then(font_id, widget::Text::font_id)
+                .and_then(font_id, widget::Text::font_id)
                 .color(default_color.plain_contrast())
                 .w(_dim[0] * 0.3)
                 .h_of(id)
@@ -147,6 +157,9 @@ impl<'a> Widget for List<'a> {
                     if let Some(ref info) = self.icon_vec.get(item.i) {
                         let mut j = Icon::new(info.clone().clone())
                             .label_color(self.style.label_color(&ui.theme));
+                        if let Some(tooltips) = self.tooltips {
+                            j = j.tooltip(tooltips);
+                        }
                         if let Some(_s) = state.selected {
                             if _s == item.i {
                                 j = j.bordered();
@@ -177,11 +190,6 @@ impl<'a> Widget for List<'a> {
 
         }
 
-
-        state.update(|state| state.frame += 2);
-        if state.frame > 180 {
-            state.update(|state| state.frame = 0);
-        }
         (state.selected, state.selected_id, state.selected_xy)
     }
 }
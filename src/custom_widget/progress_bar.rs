@@ -1,6 +1,7 @@
 use conrod_core::{widget, Positionable, Widget, Scalar, FontSize, text, Color, Labelable, Colorable,
              color};
 use conrod_core::widget::primitive::line;
+use locale::Locale;
 /// The type upon which we'll implement the `Widget` trait.
 #[derive(WidgetCommon)]
 pub struct ProgressBar<'a> {
@@ -11,6 +12,8 @@ pub struct ProgressBar<'a> {
     maybe_label: Option<&'a str>,
     len: usize,
     num_asset: usize,
+    /// When set, the label is a message key and `{loaded}` and `{total}` are filled in.
+    locale: Option<&'a Locale>,
     /// See the Style struct below.
     style: Style,
 }
@@ -52,10 +55,16 @@ impl<'a> ProgressBar<'a> {
             maybe_label: None,
             len: len,
             num_asset: num_asset,
+            locale: None,
             common: widget::CommonBuilder::default(),
             style: Style::default(),
         }
     }
+    /// Treat the label as a message key of `locale`.
+    pub fn locale(mut self, locale: &'a Locale) -> Self {
+        self.locale = Some(locale);
+        self
+    }
     builder_methods!{
         pub border { style.border = Some(Scalar) }
     }
@@ -102,7 +111,15 @@ impl<'a> Widget for ProgressBar<'a> {
         if let Some(_txt) = self.maybe_label {
             let color = self.style.label_color(&ui.theme);
             let font_size = self.style.label_font_size(&ui.theme);
-            widget::Text::new(_txt)
+            let _txt = match self.locale {
+                Some(locale) => {
+                    locale.format(_txt,
+                                  &[("loaded", &self.num_asset.to_string()),
+                                    ("total", &self.len.to_string())])
+                }
+                None => _txt.to_owned(),
+            };
+            widget::Text::new(&_txt)
                 .down_from(state.ids.outline, 0.0)
                 .font_size(font_size)
                 .color(color)
@@ -1,9 +1,29 @@
 use conrod_core::{self, widget, Positionable, Widget, Sizeable, Labelable, Colorable, color};
 use custom_widget::animated_canvas;
 use custom_widget::pad_text_button;
+use custom_widget::nine_slice::{NineSlice, NineSliceImage};
+use custom_widget::rich_text::{RichText, IconMap};
+use locale::{tr_or, Locale};
+use std::fmt;
 pub trait PromptSendable {
     fn send(&self, msg: String);
 }
+
+/// A prompt for `PromptView` that lists `errors` under `title`, one per line, with a single
+/// `close` button, e.g. for the errors of `DeckList::from_text`.
+pub fn error_prompt<PS, E>(title: &str,
+                           errors: &[E],
+                           close: &str)
+                           -> (f64, String, Vec<(String, Box<Fn(PS)>)>)
+    where E: fmt::Display
+{
+    let mut text = title.to_owned();
+    for e in errors {
+        text.push('\n');
+        text.push_str(&e.to_string());
+    }
+    (0.9, text, vec![(close.to_owned(), Box::new(|_| {}))])
+}
 /// The type upon which we'll implement the `Widget` trait.
 #[derive(WidgetCommon)]
 pub struct PromptView<'a, PS>
@@ -15,6 +35,12 @@ pub struct PromptView<'a, PS>
     common: widget::CommonBuilder,
     pub prompt: &'a mut Option<(f64, String, Vec<(String, Box<Fn(PS)>)>)>, //width%,text
     pub promptsender: PS,
+    /// Optional nine-slice frame drawn instead of the plain prompt rectangle.
+    pub frame: Option<NineSliceImage>,
+    /// When set, the prompt text is rich text markup and `{name}` draws an icon from the map.
+    pub icons: Option<&'a IconMap>,
+    /// When set, the prompt text and button labels are message keys looked up in the locale.
+    pub locale: Option<&'a Locale>,
     /// See the Style struct below.
     style: Style,
 }
@@ -46,7 +72,9 @@ widget_ids! {
         canvas,
         body,
         rect,
+        frame,
         prompt,
+        rich_prompt,
         list,
     }
 }
@@ -66,6 +94,9 @@ impl<'a, PS> PromptView<'a, PS>
         PromptView {
             prompt: prompt,
             promptsender: promptsender,
+            frame: None,
+            icons: None,
+            locale: None,
             common: widget::CommonBuilder::default(),
             style: Style::default(),
         }
@@ -76,6 +107,21 @@ impl<'a, PS> PromptView<'a, PS>
         self.style.label_font_id = Some(Some(font_id));
         self
     }
+    /// Draw the prompt box with a nine-slice image.
+    pub fn frame(mut self, frame: NineSliceImage) -> Self {
+        self.frame = Some(frame);
+        self
+    }
+    /// Render the prompt text as rich text markup with icons from `icons`.
+    pub fn icons(mut self, icons: &'a IconMap) -> Self {
+        self.icons = Some(icons);
+        self
+    }
+    /// Treat the prompt text and button labels as message keys of `locale`.
+    pub fn locale(mut self, locale: &'a Locale) -> Self {
+        self.locale = Some(locale);
+        self
+    }
     builder_methods!{
         pub color { style.color = Some(conrod_core::Color) }
         pub label_font_size{style.label_font_size = Some(conrod_core::FontSize)}
@@ -127,18 +173,40 @@ impl<'a, PS> Widget for PromptView<'a, PS>
             let num = _z.2.len();
             let label_font_size = self.style.label_font_size(&ui.theme);
             let color = self.style.color(&ui.theme);
-            widget::Rectangle::fill_with(prompt_wh, color)
+            let rect_color = if self.frame.is_some() { color::TRANSPARENT } else { color };
+            widget::Rectangle::fill_with(prompt_wh, rect_color)
                 .middle_of(state.ids.canvas)
                 .set(state.ids.rect, ui);
-            widget::Text::new(&_z.1)
-                .w(_z.0 * prompt_wh[0])
-                .h(0.5 * prompt_wh[1])
-                .font_size(label_font_size)
-                .color(color.plain_contrast())
-                .mid_top_with_margin_on(state.ids.rect, 0.1 * prompt_wh[1])
-                .set(state.ids.prompt, ui);
+            if let Some(frame) = self.frame {
+                NineSlice::new(frame)
+                    .wh_of(state.ids.rect)
+                    .middle_of(state.ids.rect)
+                    .graphics_for(state.ids.rect)
+                    .set(state.ids.frame, ui);
+            }
+            // Rich and plain prompts are different widgets, so each gets its own id.
+            let prompt_id = if let Some(icons) = self.icons {
+                RichText::new(tr_or(self.locale, &_z.1))
+                    .icons(icons)
+                    .w(_z.0 * prompt_wh[0])
+                    .h(0.5 * prompt_wh[1])
+                    .font_size(label_font_size)
+                    .color(color.plain_contrast())
+                    .mid_top_with_margin_on(state.ids.rect, 0.1 * prompt_wh[1])
+                    .set(state.ids.rich_prompt, ui);
+                state.ids.rich_prompt
+            } else {
+                widget::Text::new(tr_or(self.locale, &_z.1))
+                    .w(_z.0 * prompt_wh[0])
+                    .h(0.5 * prompt_wh[1])
+                    .font_size(label_font_size)
+                    .color(color.plain_contrast())
+                    .mid_top_with_margin_on(state.ids.rect, 0.1 * prompt_wh[1])
+                    .set(state.ids.prompt, ui);
+                state.ids.prompt
+            };
             let (mut items, _) = widget::List::flow_right(num)
-                .bottom_right_of(state.ids.prompt)
+                .bottom_right_of(prompt_id)
                 .align_middle_x_of(state.ids.rect)
                 .h(0.3 * prompt_wh[1])
                 .item_size(item_size)
@@ -149,7 +217,7 @@ impl<'a, PS> Widget for PromptView<'a, PS>
                 (vec_iter.next(), items.next(ui)) {
                 let d = pad_text_button::Button::new(2)
                     .w((prompt_wh[0] - _z.0) / (num as f64))
-                    .label(&label)
+                    .label(tr_or(self.locale, &label))
                     .label_color(color.plain_contrast())
                     .label_font_size(label_font_size);
                 let dj = item.set(d, ui);
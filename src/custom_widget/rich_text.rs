@@ -0,0 +1,610 @@
+//! Text mixed with inline icons, bold and coloured spans.
+//!
+//! The markup is kept small so that it can live in card descriptions and prompts:
+//!
+//! - `{gold}` draws the icon registered as "gold" in an `IconMap`.
+//! - `[b]..[/b]` uses the bold font.
+//! - `[color=#rrggbb]..[/color]` (or `#rrggbbaa`, or a name such as `red`) colours the span.
+//! - A newline starts a new line. `{{` and `[[` give a literal `{` and `[`.
+//!
+//! Anything that isn't recognised is drawn as plain text.
+use conrod_core::{widget, color, image, Color, Colorable, FontSize, Positionable, Rect, Scalar,
+                  Sizeable, Ui, Widget};
+use conrod_core::text::{font, Font};
+use sprite::{spriteable_rect, Spriteable};
+use text::{font_of, text_width};
+use std::collections::HashMap;
+
+/// Icons that can be named in rich text markup.
+#[derive(Clone, Debug, Default)]
+pub struct IconMap {
+    icons: HashMap<String, (image::Id, Rect)>,
+}
+impl IconMap {
+    pub fn new() -> Self {
+        IconMap::default()
+    }
+    /// Register frame `index` of a sprite sheet as the icon `name`.
+    pub fn insert<H: Spriteable>(&mut self, name: &str, image: image::Id, sprite: H, index: f64) {
+        let r = spriteable_rect(sprite, index);
+        self.insert_rect(name, image, Rect::from_corners(r.0, r.1));
+    }
+    /// Register part of an image as the icon `name`.
+    pub fn insert_rect(&mut self, name: &str, image: image::Id, src_rect: Rect) {
+        self.icons.insert(name.to_owned(), (image, src_rect));
+    }
+    pub fn get(&self, name: &str) -> Option<&(image::Id, Rect)> {
+        self.icons.get(name)
+    }
+}
+
+/// A piece of parsed markup.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Run {
+    Text {
+        text: String,
+        bold: bool,
+        color: Option<Color>,
+    },
+    Icon(String),
+    LineBreak,
+}
+
+/// Split markup into runs of text with the same style, icons and line breaks.
+pub fn parse(markup: &str) -> Vec<Run> {
+    let mut runs = vec![];
+    let mut buf = String::new();
+    let mut bold = 0usize;
+    let mut colors: Vec<Color> = vec![];
+    let mut rest = markup;
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("{{") || rest.starts_with("[[") {
+            buf.push(c);
+            rest = &rest[2..];
+            continue;
+        }
+        if c == '\n' {
+            flush(&mut runs, &mut buf, bold > 0, colors.last().cloned());
+            runs.push(Run::LineBreak);
+            rest = &rest[1..];
+            continue;
+        }
+        if c == '{' {
+            if let Some(end) = rest.find('}') {
+                flush(&mut runs, &mut buf, bold > 0, colors.last().cloned());
+                runs.push(Run::Icon(rest[1..end].trim().to_owned()));
+                rest = &rest[end + 1..];
+                continue;
+            }
+        }
+        if c == '[' {
+            if let Some(end) = rest.find(']') {
+                let tag = &rest[1..end];
+                let color_tag = if tag.starts_with("color=") {
+                    parse_color(&tag["color=".len()..])
+                } else {
+                    None
+                };
+                let known = tag == "b" || tag == "/b" || tag == "/color" || color_tag.is_some();
+                if known {
+                    flush(&mut runs, &mut buf, bold > 0, colors.last().cloned());
+                    match tag {
+                        "b" => bold += 1,
+                        "/b" => bold = bold.saturating_sub(1),
+                        "/color" => {
+                            colors.pop();
+                        }
+                        _ => colors.extend(color_tag),
+                    }
+                    rest = &rest[end + 1..];
+                    continue;
+                }
+            }
+        }
+        buf.push(c);
+        rest = &rest[c.len_utf8()..];
+    }
+    flush(&mut runs, &mut buf, bold > 0, colors.last().cloned());
+    runs
+}
+
+/// The markup with tags removed and icons replaced by a placeholder glyph, for measuring.
+pub fn plain_text(markup: &str) -> String {
+    parse(markup)
+        .into_iter()
+        .map(|run| match run {
+                 Run::Text { text, .. } => text,
+                 Run::Icon(_) => "M".to_owned(),
+                 Run::LineBreak => "\n".to_owned(),
+             })
+        .collect()
+}
+
+fn flush(runs: &mut Vec<Run>, buf: &mut String, bold: bool, color: Option<Color>) {
+    if !buf.is_empty() {
+        runs.push(Run::Text {
+                      text: buf.clone(),
+                      bold: bold,
+                      color: color,
+                  });
+        buf.clear();
+    }
+}
+
+/// `#rrggbb`, `#rrggbbaa` or one of the basic colour names.
+fn parse_color(s: &str) -> Option<Color> {
+    let s = s.trim();
+    if s.starts_with('#') {
+        let hex = &s[1..];
+        let byte = |i: usize| hex.get(i..i + 2).and_then(|b| u8::from_str_radix(b, 16).ok());
+        return match hex.len() {
+            6 => {
+                match (byte(0), byte(2), byte(4)) {
+                    (Some(r), Some(g), Some(b)) => Some(color::rgb_bytes(r, g, b)),
+                    _ => None,
+                }
+            }
+            8 => {
+                match (byte(0), byte(2), byte(4), byte(6)) {
+                    (Some(r), Some(g), Some(b), Some(a)) => {
+                        Some(color::rgba_bytes(r, g, b, a as f32 / 255.0))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        };
+    }
+    match s {
+        "red" => Some(color::RED),
+        "orange" => Some(color::ORANGE),
+        "yellow" => Some(color::YELLOW),
+        "green" => Some(color::GREEN),
+        "blue" => Some(color::BLUE),
+        "purple" => Some(color::PURPLE),
+        "brown" => Some(color::BROWN),
+        "white" => Some(color::WHITE),
+        "grey" | "gray" => Some(color::GREY),
+        "black" => Some(color::BLACK),
+        _ => None,
+    }
+}
+
+/// Something placed by `layout`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Piece {
+    Text {
+        text: String,
+        bold: bool,
+        color: Option<Color>,
+    },
+    Icon(String),
+}
+
+/// A `Piece` on line `line`, `x` from the left edge and `w` wide.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Placed {
+    pub piece: Piece,
+    pub line: usize,
+    pub x: Scalar,
+    pub w: Scalar,
+}
+
+/// Wrap runs at whitespace to fit `max_w`.
+///
+/// `measure(text, bold)` gives the width of some text and icons are `icon_w` wide. Neighbouring
+/// words of the same style are merged so that each line needs as few widgets as possible.
+pub fn layout<F>(runs: &[Run], max_w: Scalar, icon_w: Scalar, measure: F) -> Vec<Placed>
+    where F: Fn(&str, bool) -> Scalar
+{
+    let mut placed: Vec<Placed> = vec![];
+    let (mut line, mut x) = (0, 0.0);
+    for run in runs.iter() {
+        match *run {
+            Run::LineBreak => {
+                line += 1;
+                x = 0.0;
+            }
+            Run::Icon(ref name) => {
+                if x > 0.0 && x + icon_w > max_w {
+                    wrap(&mut placed, &measure);
+                    line += 1;
+                    x = 0.0;
+                }
+                placed.push(Placed {
+                                piece: Piece::Icon(name.clone()),
+                                line: line,
+                                x: x,
+                                w: icon_w,
+                            });
+                x += icon_w;
+            }
+            Run::Text { ref text, bold, color } => {
+                for word in words(text) {
+                    let is_space = word.chars().all(char::is_whitespace);
+                    if is_space && x == 0.0 {
+                        continue;
+                    }
+                    let w = measure(word, bold);
+                    if !is_space && x > 0.0 && x + w > max_w {
+                        wrap(&mut placed, &measure);
+                        line += 1;
+                        x = 0.0;
+                    }
+                    // Everything on a line is placed left to right, so the last piece is the
+                    // neighbour of this word if it's on the same line.
+                    let merge = match placed.last() {
+                        Some(&Placed { piece: Piece::Text { bold: b, color: c, .. }, line: l, .. }) => {
+                            b == bold && c == color && l == line
+                        }
+                        _ => false,
+                    };
+                    if merge {
+                        if let Some(&mut Placed { piece: Piece::Text { text: ref mut t, .. },
+                                                  w: ref mut pw,
+                                                  .. }) = placed.last_mut() {
+                            t.push_str(word);
+                            *pw += w;
+                        }
+                    } else {
+                        placed.push(Placed {
+                                        piece: Piece::Text {
+                                            text: word.to_owned(),
+                                            bold: bold,
+                                            color: color,
+                                        },
+                                        line: line,
+                                        x: x,
+                                        w: w,
+                                    });
+                    }
+                    x += w;
+                }
+            }
+        }
+    }
+    placed
+}
+
+/// Drop the whitespace at the end of the line being wrapped.
+fn wrap<F>(placed: &mut Vec<Placed>, measure: &F)
+    where F: Fn(&str, bool) -> Scalar
+{
+    let empty = match placed.last_mut() {
+        Some(&mut Placed { piece: Piece::Text { ref mut text, bold, .. }, ref mut w, .. }) => {
+            let len = text.trim_end().len();
+            *w -= measure(&text[len..], bold);
+            text.truncate(len);
+            text.is_empty()
+        }
+        _ => false,
+    };
+    if empty {
+        placed.pop();
+    }
+}
+
+/// Split into alternating runs of whitespace and non-whitespace.
+fn words(s: &str) -> Vec<&str> {
+    let mut out = vec![];
+    let mut start = 0;
+    let mut prev_space = None;
+    for (i, c) in s.char_indices() {
+        let space = c.is_whitespace();
+        if prev_space.map_or(false, |p| p != space) {
+            out.push(&s[start..i]);
+            start = i;
+        }
+        prev_space = Some(space);
+    }
+    if start < s.len() {
+        out.push(&s[start..]);
+    }
+    out
+}
+
+/// The word of `text` that is `x` from its left edge, trimmed of punctuation.
+fn word_at<F>(text: &str, x: Scalar, measure: F) -> Option<String>
+    where F: Fn(&str) -> Scalar
+{
+    let mut left = 0.0;
+    for word in words(text) {
+        left += measure(word);
+        if x < left {
+            let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+            return if word.is_empty() {
+                None
+            } else {
+                Some(word.to_owned())
+            };
+        }
+    }
+    None
+}
+
+/// The type upon which we'll implement the `Widget` trait.
+#[derive(WidgetCommon)]
+pub struct RichText<'a> {
+    /// An object that handles some of the dirty work of rendering a GUI. We don't
+    /// really have to worry about it.
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    pub text: &'a str,
+    pub icons: Option<&'a IconMap>,
+    /// See the Style struct below.
+    style: Style,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
+pub struct Style {
+    /// Color of text outside any `[color]` span.
+    #[conrod(default = "theme.label_color")]
+    pub color: Option<Color>,
+    #[conrod(default = "theme.font_size_medium")]
+    pub font_size: Option<FontSize>,
+    /// Specify a unique font for the text.
+    #[conrod(default = "theme.font_id")]
+    pub font_id: Option<Option<font::Id>>,
+    /// The font used inside `[b]` spans. Defaults to `font_id`.
+    #[conrod(default = "None")]
+    pub bold_font_id: Option<Option<font::Id>>,
+    /// The distance between lines.
+    #[conrod(default = "1.0")]
+    pub line_spacing: Option<Scalar>,
+    /// Icon size as a multiple of the font size.
+    #[conrod(default = "1.0")]
+    pub icon_scale: Option<f64>,
+}
+
+widget_ids! {
+    struct Ids {
+        texts[],
+        icons[],
+    }
+}
+
+/// Represents the unique, cached state for our RichText widget.
+pub struct State {
+    ids: Ids,
+}
+
+impl<'a> RichText<'a> {
+    /// Create a button context to be built upon.
+    pub fn new(text: &'a str) -> Self {
+        RichText {
+            text: text,
+            icons: None,
+            common: widget::CommonBuilder::default(),
+            style: Style::default(),
+        }
+    }
+    /// Icons that `{name}` markup refers to. Without a map, icons are drawn as their markup.
+    pub fn icons(mut self, icons: &'a IconMap) -> Self {
+        self.icons = Some(icons);
+        self
+    }
+    /// Specify the font used for the text.
+    pub fn font_id(mut self, font_id: font::Id) -> Self {
+        self.style.font_id = Some(Some(font_id));
+        self
+    }
+    /// Specify the font used inside `[b]` spans.
+    pub fn bold_font_id(mut self, font_id: font::Id) -> Self {
+        self.style.bold_font_id = Some(Some(font_id));
+        self
+    }
+    builder_methods!{
+        pub font_size { style.font_size = Some(FontSize) }
+        pub line_spacing { style.line_spacing = Some(Scalar) }
+        pub icon_scale { style.icon_scale = Some(f64) }
+    }
+    /// The `[w, h]` the text takes up once wrapped to fit `max_w`, e.g. to size a box around it.
+    pub fn size(&self, ui: &Ui, max_w: Scalar) -> [Scalar; 2] {
+        let (regular, bold) = match self.fonts(ui) {
+            Some(fonts) => fonts,
+            None => return [0.0, 0.0],
+        };
+        let font_size = self.style.font_size(&ui.theme);
+        let icon_w = font_size as Scalar * self.style.icon_scale(&ui.theme);
+        let line_h = font_size as Scalar + self.style.line_spacing(&ui.theme);
+        let placed = layout(&self.runs(), max_w, icon_w, |s, b| {
+            text_width(if b { bold } else { regular }, font_size, s)
+        });
+        let w = placed.iter().fold(0.0, |w: Scalar, p| w.max(p.x + p.w));
+        let lines = placed.iter().map(|p| p.line + 1).max().unwrap_or(0);
+        [w.ceil(), lines as Scalar * line_h]
+    }
+    /// The regular and bold fonts.
+    fn fonts<'u>(&self, ui: &'u Ui) -> Option<(&'u Font, &'u Font)> {
+        let font_id = self.style.font_id(&ui.theme);
+        let bold_font_id = self.style.bold_font_id(&ui.theme).or(font_id);
+        font_of(ui, font_id).map(|regular| (regular, font_of(ui, bold_font_id).unwrap_or(regular)))
+    }
+    /// The parsed markup, with icons missing from the map turned back into text.
+    fn runs(&self) -> Vec<Run> {
+        let icons = self.icons;
+        parse(self.text)
+            .into_iter()
+            .map(|run| match run {
+                     Run::Icon(name) => {
+                         if icons.and_then(|m| m.get(&name)).is_some() {
+                             Run::Icon(name)
+                         } else {
+                             Run::Text {
+                                 text: format!("{{{}}}", name),
+                                 bold: false,
+                                 color: None,
+                             }
+                         }
+                     }
+                     run => run,
+                 })
+            .collect()
+    }
+}
+
+/// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
+/// documentation for more details.
+impl<'a> Widget for RichText<'a> {
+    /// The State struct that we defined above.
+    type State = State;
+    /// The Style struct that we defined using the `widget_style!` macro.
+    type Style = Style;
+    /// The event produced by instantiating the widget.
+    ///
+    /// The word under the mouse, without punctuation, if any.
+    type Event = Option<String>;
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State { ids: Ids::new(id_gen) }
+    }
+
+    fn style(&self) -> Self::Style {
+        self.style.clone()
+    }
+
+    /// Update the state of the button by handling any input that has occurred since the last
+    /// update.
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { id, state, rect, ui, .. } = args;
+        let font_size = self.style.font_size(&ui.theme);
+        let font_id = self.style.font_id(&ui.theme);
+        let bold_font_id = self.style.bold_font_id(&ui.theme).or(font_id);
+        let line_h = font_size as Scalar + self.style.line_spacing(&ui.theme);
+        let icon_w = font_size as Scalar * self.style.icon_scale(&ui.theme);
+        let default_color = self.style.color(&ui.theme);
+        let icons = self.icons;
+        let (placed, hovered) = {
+            let (regular, bold_font) = match self.fonts(ui) {
+                Some(fonts) => fonts,
+                None => return None,
+            };
+            let measure = |s: &str, b: bool| {
+                text_width(if b { bold_font } else { regular }, font_size, s)
+            };
+            let placed = layout(&self.runs(), rect.w(), icon_w, &measure);
+            // Mouse positions are relative to the middle of the widget.
+            let hovered = ui.widget_input(id).mouse().and_then(|mouse| {
+                let rel = mouse.rel_xy();
+                let (x, y) = (rel[0] + rect.w() / 2.0, rect.h() / 2.0 - rel[1]);
+                if y < 0.0 {
+                    return None;
+                }
+                let line = (y / line_h) as usize;
+                placed.iter()
+                    .filter(|p| p.line == line && x >= p.x && x < p.x + p.w)
+                    .filter_map(|p| match p.piece {
+                                    Piece::Text { ref text, bold, .. } => {
+                                        word_at(text, x - p.x, |s| measure(s, bold))
+                                    }
+                                    Piece::Icon(_) => None,
+                                })
+                    .next()
+            });
+            (placed, hovered)
+        };
+
+        let num_icons = placed.iter()
+            .filter(|p| match p.piece {
+                        Piece::Icon(_) => true,
+                        _ => false,
+                    })
+            .count();
+        let num_texts = placed.len() - num_icons;
+        if state.ids.texts.len() < num_texts || state.ids.icons.len() < num_icons {
+            let id_gen = &mut ui.widget_id_generator();
+            state.update(|state| {
+                             let (t, i) = (state.ids.texts.len(), state.ids.icons.len());
+                             state.ids.texts.resize(t.max(num_texts), id_gen);
+                             state.ids.icons.resize(i.max(num_icons), id_gen);
+                         });
+        }
+
+        let (mut text_i, mut icon_i) = (0, 0);
+        for p in placed.iter() {
+            let top = p.line as Scalar * line_h;
+            match p.piece {
+                Piece::Text { ref text, bold, color } => {
+                    widget::Text::new(text)
+                        .font_size(font_size)
+                        .color(color.unwrap_or(default_color))
+                        .and_then(if bold { bold_font_id } else { font_id },
+                                  widget::Text::font_id)
+                        .no_line_wrap()
+                        .top_left_with_margins_on(id, top, p.x)
+                        .parent(id)
+                        .graphics_for(id)
+                        .set(state.ids.texts[text_i], ui);
+                    text_i += 1;
+                }
+                Piece::Icon(ref name) => {
+                    if let Some(&(image, src_rect)) = icons.and_then(|m| m.get(name)) {
+                        widget::Image::new(image)
+                            .source_rectangle(src_rect)
+                            .w_h(icon_w, icon_w)
+                            .top_left_with_margins_on(id,
+                                                      top + (font_size as Scalar - icon_w) / 2.0,
+                                                      p.x)
+                            .parent(id)
+                            .graphics_for(id)
+                            .set(state.ids.icons[icon_i], ui);
+                    }
+                    icon_i += 1;
+                }
+            }
+        }
+        hovered
+    }
+}
+impl<'a> Colorable for RichText<'a> {
+    builder_method!(color { style.color = Some(Color) });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(text: &str, bold: bool, color: Option<Color>) -> Run {
+        Run::Text {
+            text: text.to_owned(),
+            bold: bold,
+            color: color,
+        }
+    }
+
+    #[test]
+    fn icons_tags_and_line_breaks() {
+        assert_eq!(parse("Pay {gold}: [b]draw[/b] [color=red]a card[/color]\nok"),
+                   vec![text("Pay ", false, None),
+                        Run::Icon("gold".to_owned()),
+                        text(": ", false, None),
+                        text("draw", true, None),
+                        text(" ", false, None),
+                        text("a card", false, Some(color::RED)),
+                        Run::LineBreak,
+                        text("ok", false, None)]);
+    }
+
+    #[test]
+    fn doubled_brackets_are_literal() {
+        assert_eq!(parse("{{gold} and [[b]"), vec![text("{gold} and [b]", false, None)]);
+        assert_eq!(plain_text("[b]{{[/b]{gold}"), "{M");
+    }
+
+    #[test]
+    fn unclosed_and_unknown_tags_are_text() {
+        assert_eq!(parse("[b]bold {gold [i] [color=nope] [b"),
+                   vec![text("bold {gold [i] [color=nope] [b", true, None)]);
+        assert_eq!(parse("[/b]x[/color]"), vec![text("x", false, None)]);
+    }
+
+    #[test]
+    fn colors_nest() {
+        let red = color::rgb_bytes(0xff, 0, 0);
+        assert_eq!(parse("[color=#ff0000]a[color=blue]b[/color]c[/color]d"),
+                   vec![text("a", false, Some(red)),
+                        text("b", false, Some(color::BLUE)),
+                        text("c", false, Some(red)),
+                        text("d", false, None)]);
+    }
+}
@@ -5,6 +5,8 @@ use conrod_core::{Color, Colorable, Borderable, Positionable, UiCell, Widget, ev
 use conrod_core::position::{Rect, Scalar, Dimensions, Point};
 use conrod_core::widget;
 use custom_widget::dragdrop_list::Draggable;
+use clock::{frames_since, Clock, SYSTEM_CLOCK};
+use std::time::Instant;
 pub use sprite::{Spriteable, spriteable_rect};
 /// The `Button` displays an `Image` on top.
 #[derive(Copy, Clone)]
@@ -22,11 +24,13 @@ pub struct Image<H>
 }
 /// A pressable button widget whose reaction is triggered upon release.
 #[derive(WidgetCommon)]
-pub struct Button<S> {
+pub struct Button<'a, S> {
     #[conrod(common_builder)]
     common: widget::CommonBuilder,
     /// Whether the `Button` is a `Flat` color or an `Image`.
     pub show: S,
+    /// Times how long the button has been held.
+    pub clock: &'a Clock,
     /// Unique styling parameters for the Button.
     pub style: Style,
 }
@@ -58,8 +62,8 @@ pub struct ImageState {
 /// Track whether some sort of dragging is currently occurring.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Drag {
-    /// The drag is currently selecting a range of text.
-    Selecting(u16, Point),
+    /// The button is held: when the hold (or the last toggle) started, and the mouse position.
+    Selecting(Instant, Point),
     None,
     Terminate,
 }
@@ -77,17 +81,18 @@ widget_ids! {
 
 
 
-impl<S> Button<S> {
+impl<'a, S> Button<'a, S> {
     /// Create a button context to be built upon.
     fn new_internal(show: S) -> Self {
         Button {
             common: widget::CommonBuilder::default(),
             show: show,
+            clock: &SYSTEM_CLOCK,
             style: Style::default(),
         }
     }
 }
-impl<H> Button<Image<H>>
+impl<'a, H> Button<'a, Image<H>>
     where H: Spriteable
 {
     /// Begin building a button displaying the given `Image` on top.
@@ -114,8 +119,13 @@ impl<H> Button<Image<H>>
         self.show.source_rectangle = Some(rect);
         self
     }
+    /// Read the time from `clock` instead of the system clock.
+    pub fn clock(mut self, clock: &'a Clock) -> Self {
+        self.clock = clock;
+        self
+    }
 }
-impl<H> Widget for Button<Image<H>>
+impl<'a, H> Widget for Button<'a, Image<H>>
     where H: Spriteable
 {
     type State = ImageState;
@@ -137,10 +147,10 @@ impl<H> Widget for Button<Image<H>>
     /// Update the state of the Button.
     fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
         let widget::UpdateArgs { id, state, style, rect, ui, .. } = args;
-        let Button { show, .. } = self;
+        let Button { show, clock, .. } = self;
         let mut drag = state.drag;
         let mut toggle_bool = state.toggle_bool;
-        update_drag(id, &mut drag, ui);
+        update_drag(id, &mut drag, clock.now(), ui);
         bordered_rectangle(id,
                            state.ids.rectangle,
                            rect,
@@ -148,7 +158,8 @@ impl<H> Widget for Button<Image<H>>
                            style,
                            ui);
 
-        let draw_spinner_index = update_toggle_bool_spinner_index(&mut drag, &mut toggle_bool);
+        let draw_spinner_index =
+            update_toggle_bool_spinner_index(&mut drag, &mut toggle_bool, clock);
         state.update(|state| {
                          state.drag = drag;
                          state.toggle_bool = toggle_bool
@@ -188,7 +199,7 @@ impl<H> Widget for Button<Image<H>>
     }
 }
 
-fn update_drag(button_id: widget::Id, drag: &mut Drag, ui: &UiCell) {
+fn update_drag(button_id: widget::Id, drag: &mut Drag, now: Instant, ui: &UiCell) {
     for widget_event in ui.widget_input(button_id).events() {
         match widget_event {
             event::Widget::Press(press) => {
@@ -197,7 +208,7 @@ fn update_drag(button_id: widget::Id, drag: &mut Drag, ui: &UiCell) {
                         match drag {
                             &mut Drag::Selecting(_, _) => {}
                             &mut Drag::None => {
-                                *drag = Drag::Selecting(0, point);
+                                *drag = Drag::Selecting(now, point);
                             }
                             &mut Drag::Terminate => {}
                         }
@@ -271,38 +282,41 @@ fn draw_spinner_op<H: Spriteable>(button_id: widget::Id,
     }
 
 }
-fn update_toggle_bool_spinner_index(drag: &mut Drag, toggle_bool: &mut bool) -> Option<u16> {
+fn update_toggle_bool_spinner_index(drag: &mut Drag,
+                                    toggle_bool: &mut bool,
+                                    clock: &Clock)
+                                    -> Option<u16> {
     match drag {
-        &mut Drag::Selecting(ref mut spinner_index, _) => {
-            if *spinner_index >= 60 {
+        &mut Drag::Selecting(ref mut held_since, _) => {
+            // One spinner frame per frame at `clock::NOMINAL_FPS`, toggling after 60.
+            let spinner_index = frames_since(clock, *held_since);
+            if spinner_index >= 60.0 {
                 if *toggle_bool {
                     *toggle_bool = false;
                 } else {
                     *toggle_bool = true;
                 }
 
-                *spinner_index = 0;
+                *held_since = clock.now();
                 None
             } else {
-
-                *spinner_index += 1;
-                Some(spinner_index.clone())
+                Some(spinner_index as u16 + 1)
             }
         }
         _ => None,
     }
 }
-impl<S> Colorable for Button<S> {
+impl<'a, S> Colorable for Button<'a, S> {
     builder_method!(color { style.color = Some(Color) });
 }
 
-impl<S> Borderable for Button<S> {
+impl<'a, S> Borderable for Button<'a, S> {
     builder_methods!{
         border { style.border = Some(Scalar) }
         border_color { style.border_color = Some(Color) }
     }
 }
-impl<S> Draggable for Button<S> {
+impl<'a, S> Draggable for Button<'a, S> {
     builder_methods!{
         draggable { style.draggable = Some(bool) }
     }
@@ -0,0 +1,191 @@
+//! Shuffling and dealing a deck, so that the `Shuffle` animation and the game agree on who got
+//! which card.
+//!
+//! The same seed always gives the same shuffle, on every platform, so a seed sent from the
+//! server or saved with a replay reproduces the deal exactly.
+use std::error::Error;
+use std::fmt;
+
+/// The SplitMix64 generator: small, fast and fully determined by its seed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SplitMix64 {
+    state: u64,
+}
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    /// A number in `0..n`, without the bias of taking the remainder. `n` must not be 0.
+    pub fn below(&mut self, n: u64) -> u64 {
+        // Reject the top values that would make the low remainders more likely.
+        let zone = u64::max_value() - u64::max_value() % n;
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return x % n;
+            }
+        }
+    }
+}
+
+/// A Fisher–Yates shuffle of `0..len`: entry `i` is the index, in the unshuffled deck, of the
+/// card that ends up at position `i`.
+pub fn permutation(len: usize, seed: u64) -> Vec<usize> {
+    let mut rng = SplitMix64::new(seed);
+    let mut order: Vec<usize> = (0..len).collect();
+    for i in (1..len).rev() {
+        let j = rng.below(i as u64 + 1) as usize;
+        order.swap(i, j);
+    }
+    order
+}
+
+/// Why a deal couldn't be made.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DealError {
+    /// The seats want more cards than the deck has.
+    NotEnoughCards { needed: usize, available: usize },
+}
+
+impl fmt::Display for DealError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DealError::NotEnoughCards { needed, available } => {
+                write!(f, "dealing needs {} cards but the deck has {}", needed, available)
+            }
+        }
+    }
+}
+
+impl Error for DealError {}
+
+/// A shuffled deck dealt to a number of seats.
+///
+/// Cards are dealt one at a time from the top of the shuffled deck, round the table starting at
+/// seat 0, skipping seats that already have their count. All indices refer to the unshuffled
+/// deck, i.e. the `values` given to `Shuffle`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deal {
+    pub seed: u64,
+    /// The shuffled deck, top card first.
+    pub order: Vec<usize>,
+    /// The cards each seat received, in the order they were dealt.
+    pub seats: Vec<Vec<usize>>,
+    /// `(card, seat)` for every card dealt, in the order they were dealt.
+    pub dealt: Vec<(usize, usize)>,
+    /// The cards left over, top card first.
+    pub rest: Vec<usize>,
+}
+
+impl Deal {
+    /// Shuffle a deck of `len` cards with `seed` and deal `counts[seat]` cards to each seat.
+    pub fn new(len: usize, seed: u64, counts: &[usize]) -> Result<Deal, DealError> {
+        let needed = counts.iter().sum();
+        if needed > len {
+            return Err(DealError::NotEnoughCards {
+                           needed: needed,
+                           available: len,
+                       });
+        }
+        let order = permutation(len, seed);
+        let mut seats = vec![vec![]; counts.len()];
+        let mut dealt = vec![];
+        let mut top = order.iter();
+        while dealt.len() < needed {
+            for (seat, &count) in counts.iter().enumerate() {
+                if seats[seat].len() < count {
+                    // There are enough cards, checked above.
+                    let card = *top.next().unwrap();
+                    seats[seat].push(card);
+                    dealt.push((card, seat));
+                }
+            }
+        }
+        Ok(Deal {
+               seed: seed,
+               rest: top.cloned().collect(),
+               order: order,
+               seats: seats,
+               dealt: dealt,
+           })
+    }
+    /// Deal the same number of cards to each of `seats` seats.
+    pub fn even(len: usize, seed: u64, seats: usize, each: usize) -> Result<Deal, DealError> {
+        Deal::new(len, seed, &vec![each; seats])
+    }
+    /// The cards dealt, in the order they were dealt, for `Shuffle::give_out`.
+    pub fn give_out(&self) -> Vec<usize> {
+        self.dealt.iter().map(|&(card, _)| card).collect()
+    }
+    /// The seat that received `card`, if it was dealt.
+    pub fn seat_of(&self, card: usize) -> Option<usize> {
+        self.dealt.iter().find(|&&(c, _)| c == card).map(|&(_, seat)| seat)
+    }
+    /// `values` in shuffled order, top card first.
+    pub fn shuffled<T: Clone>(&self, values: &[T]) -> Vec<T> {
+        self.order.iter().map(|&i| values[i].clone()).collect()
+    }
+    /// The values each seat received.
+    pub fn hands<T: Clone>(&self, values: &[T]) -> Vec<Vec<T>> {
+        self.seats
+            .iter()
+            .map(|seat| seat.iter().map(|&i| values[i].clone()).collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splitmix_matches_the_reference() {
+        let mut rng = SplitMix64::new(0);
+        assert_eq!(rng.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(rng.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+    }
+
+    #[test]
+    fn permutation_is_a_shuffle_fixed_by_the_seed() {
+        let order = permutation(52, 7);
+        let mut sorted = order.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..52).collect::<Vec<_>>());
+        assert_eq!(permutation(52, 7), order);
+        assert!(permutation(52, 8) != order);
+        assert!(permutation(0, 7).is_empty());
+        assert_eq!(permutation(1, 7), vec![0]);
+    }
+
+    #[test]
+    fn deal_goes_round_the_seats() {
+        let deal = Deal::new(10, 42, &[3, 1, 2]).unwrap();
+        assert_eq!(deal, Deal::new(10, 42, &[3, 1, 2]).unwrap());
+        let seats = deal.dealt.iter().map(|&(_, seat)| seat).collect::<Vec<_>>();
+        assert_eq!(seats, vec![0, 1, 2, 0, 2, 0]);
+        assert_eq!(deal.give_out(), deal.order[..6].to_vec());
+        assert_eq!(deal.rest, deal.order[6..].to_vec());
+        assert_eq!(deal.seats.iter().map(|s| s.len()).collect::<Vec<_>>(), vec![3, 1, 2]);
+        assert_eq!(deal.seat_of(deal.order[1]), Some(1));
+        assert_eq!(deal.seat_of(deal.order[6]), None);
+        let values = (0..10).map(|i| i * 10).collect::<Vec<_>>();
+        assert_eq!(deal.hands(&values)[1], vec![deal.order[1] * 10]);
+    }
+
+    #[test]
+    fn dealing_more_than_the_deck_is_an_error() {
+        assert_eq!(Deal::even(5, 1, 2, 3),
+                   Err(DealError::NotEnoughCards {
+                           needed: 6,
+                           available: 5,
+                       }));
+        assert_eq!(Deal::even(6, 1, 2, 3).map(|d| d.rest.len()), Ok(0));
+    }
+}
@@ -1,7 +1,14 @@
-use conrod_core::{widget, Positionable, Widget, Sizeable, UiCell};
+use conrod_core::{widget, Point, Positionable, Widget, Sizeable, Ui, UiCell};
 use conrod_core::widget::primitive::image::Image;
+use clock::{secs, Clock, NOMINAL_FPS, SYSTEM_CLOCK};
+use tween::{Easing, Lerp};
+use std::f64::consts::PI;
 use std::fmt::Debug;
+use std::time::{Duration, Instant};
 use std::marker::Send;
+pub mod deal;
+
+pub use custom_widget::shuffle::deal::{Deal, DealError};
 #[derive(WidgetCommon)]
 pub struct Shuffle<'a, T, W>
     where T: Clone + Send + 'a + Debug,
@@ -15,7 +22,22 @@ pub struct Shuffle<'a, T, W>
     widget_closure: Box<Fn(T) -> W>,
     pub back_card: Image,
     pub give_out: Option<Vec<usize>>,
+    /// The seat each card of `give_out` goes to. Round the seats in turn when `None`.
+    pub give_to: Option<Vec<usize>>,
+    /// Where cards are dealt to. When empty, they are laid out in the widget as it was shown.
+    pub seats: Vec<Seat>,
+    pub clock: &'a Clock,
+    /// Hold the routine where it is while `true`.
+    pub paused: bool,
+    /// Jump to the end of the routine on this update.
+    pub skip: bool,
+    /// Play the routine again from the start on this update.
+    pub restart: bool,
 }
+/// The most backs drawn at once by the `Riffle` and `Fan` routines.
+const FAN_CARDS: usize = 5;
+/// How far each card dealt to a seat is moved along from the one before, in card widths.
+const SEAT_OVERLAP: f64 = 0.25;
 #[derive(Debug)]
 enum AniState {
     Waitthen,
@@ -25,27 +47,113 @@ enum AniState {
     Giveout(u16),
     Reset,
 }
+/// Where a seat is.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SeatTarget {
+    /// An absolute position.
+    Point(Point),
+    /// The middle of a widget, e.g. a player's hand or avatar.
+    Widget(widget::Id),
+}
+impl SeatTarget {
+    fn xy(&self, ui: &Ui) -> Option<Point> {
+        match *self {
+            SeatTarget::Point(xy) => Some(xy),
+            SeatTarget::Widget(id) => ui.xy_of(id),
+        }
+    }
+}
+
+/// A player's place at the table that cards are dealt to.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Seat {
+    pub target: SeatTarget,
+    /// Whether the cards dealt here show their face, e.g. `true` for our own seat and `false`
+    /// for opponents, who get `back_card`.
+    pub face_up: bool,
+}
+impl Seat {
+    pub fn new(target: SeatTarget, face_up: bool) -> Self {
+        Seat {
+            target: target,
+            face_up: face_up,
+        }
+    }
+}
+
+/// What the cards do while they are being shuffled.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Routine {
+    /// The deck is collected into one pile and a second back wobbles over it.
+    Wobble,
+    /// The deck splits into two halves that are riffled back together.
+    Riffle,
+    /// The deck spreads out into a fan and closes again.
+    Fan,
+}
+
+/// The part of the routine that is playing.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Phase {
+    /// The cards are laid out face up.
+    Showing,
+    /// The cards are sliding onto each other.
+    Collecting,
+    /// The collected deck is being shuffled.
+    Shuffling,
+    /// The given card of `give_out` is being dealt.
+    Dealing(usize),
+    /// The routine has played through or was skipped. It stays done until `restart`.
+    Done,
+}
+
+/// The event produced by `Shuffle` on every update.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ShuffleEvent {
+    pub phase: Phase,
+    /// How far through the whole routine, from 0 to 1.
+    pub progress: f64,
+    pub paused: bool,
+}
+impl ShuffleEvent {
+    /// `true` once the routine has played through.
+    pub fn is_finished(&self) -> bool {
+        self.phase == Phase::Done
+    }
+}
+
 #[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
 pub struct Style {
     #[conrod(default = "[220.0,260.0]")]
     pub image_dim: Option<[f64; 2]>,
-    /// The number of frames to keep one card
+    /// The number of frames, at `clock::NOMINAL_FPS`, to keep one card
     #[conrod(default="60")]
     pub close_frame_rate: Option<u16>,
+    /// The curve a card follows as it slides during the keep and give out phases
+    #[conrod(default="Easing::Linear")]
+    pub easing: Option<Easing>,
+    /// What the cards do while they are being shuffled
+    #[conrod(default="Routine::Wobble")]
+    pub routine: Option<Routine>,
 }
 widget_ids! {
     struct Ids {
         listview,
         items[],
         backview,
-        backview2
+        backview2,
+        backs[],
+        seat_backs[],
     }
 }
 /// Represents the unique, cached state for our CardViewPartial widget.
 pub struct State {
     ids: Ids,
-    frame: u16,
-    num_closed: i8,
+    start: Instant,
+    /// When the routine was paused, while it is.
+    paused_at: Option<Instant>,
+    /// Whether the routine has reached `Phase::Done`.
+    done: bool,
 }
 
 impl<'a, T, W> Shuffle<'a, T, W>
@@ -59,6 +167,12 @@ impl<'a, T, W> Shuffle<'a, T, W>
             widget_closure: widget_closure,
             back_card: back_card,
             give_out: None,
+            give_to: None,
+            seats: vec![],
+            clock: &SYSTEM_CLOCK,
+            paused: false,
+            skip: false,
+            restart: false,
             common: widget::CommonBuilder::default(),
             style: Style::default(),
         }
@@ -66,11 +180,49 @@ impl<'a, T, W> Shuffle<'a, T, W>
     builder_methods!{
         pub image_dim { style.image_dim = Some([f64;2]) }
         pub close_frame_rate { style.close_frame_rate = Some(u16) }
+        pub easing { style.easing = Some(Easing) }
+        pub routine { style.routine = Some(Routine) }
     }
     pub fn give_out(mut self, _giveout: Vec<usize>) -> Self {
         self.give_out = Some(_giveout);
         self
     }
+    /// Give out the cards of `deal` in the order they were dealt, each to the seat it was dealt
+    /// to. `values` should be the unshuffled deck that `deal` was made for.
+    pub fn deal(mut self, deal: &Deal) -> Self {
+        self.give_to = Some(deal.dealt.iter().map(|&(_, seat)| seat).collect());
+        self.give_out(deal.give_out())
+    }
+    /// Deal the cards of `give_out` to `seats` instead of laying them out in the widget.
+    pub fn seats(mut self, seats: Vec<Seat>) -> Self {
+        self.seats = seats;
+        self
+    }
+    /// The seat the `i`th card of `give_out` goes to.
+    fn seat_for(&self, i: usize) -> usize {
+        let n = self.seats.len().max(1);
+        self.give_to.as_ref().and_then(|g| g.get(i)).map_or(i, |&seat| seat) % n
+    }
+    /// Read the time from `clock` instead of the system clock.
+    pub fn clock(mut self, clock: &'a Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+    /// Hold the routine where it is while `paused`, and carry on from there once it isn't.
+    pub fn paused(mut self, paused: bool) -> Self {
+        self.paused = paused;
+        self
+    }
+    /// Jump to the end of the routine, e.g. when the player doesn't want to watch it.
+    pub fn skip_to_end(mut self) -> Self {
+        self.skip = true;
+        self
+    }
+    /// Play the routine again from the start, e.g. to deal the next hand.
+    pub fn restart(mut self) -> Self {
+        self.restart = true;
+        self
+    }
 }
 
 /// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
@@ -84,15 +236,14 @@ impl<'a, T, W> Widget for Shuffle<'a, T, W>
     /// The Style struct that we defined using the `widget_style!` macro.
     type Style = Style;
     /// The event produced by instantiating the widget.
-    ///
-    /// `Some` when clicked, otherwise `None`.
-    type Event = bool;
+    type Event = ShuffleEvent;
 
     fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
         State {
             ids: Ids::new(id_gen),
-            frame: 0,
-            num_closed: 0,
+            start: self.clock.now(),
+            paused_at: None,
+            done: false,
         }
     }
 
@@ -103,33 +254,78 @@ impl<'a, T, W> Widget for Shuffle<'a, T, W>
     /// Update the state of the button by handling any input that has occurred since the last
     /// update.
     fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
-        let widget::UpdateArgs { id, state, ui, .. } = args;
+        let widget::UpdateArgs { id, state, rect, ui, .. } = args;
         let image_dim = self.style.image_dim(ui.theme());
         let len = self.values.len();
         if state.ids.items.len() < len {
             let id_gen = &mut ui.widget_id_generator();
             state.update(|state| state.ids.items.resize(len, id_gen));
         }
+        if state.ids.seat_backs.len() < len {
+            let id_gen = &mut ui.widget_id_generator();
+            state.update(|state| state.ids.seat_backs.resize(len, id_gen));
+        }
+        if state.ids.backs.len() < FAN_CARDS {
+            let id_gen = &mut ui.widget_id_generator();
+            state.update(|state| state.ids.backs.resize(FAN_CARDS, id_gen));
+        }
         let close_frame_rate = self.style.close_frame_rate(ui.theme()); //frames to slot the last card to the back of its previous
+        let easing = self.style.easing(ui.theme());
+        let routine = self.style.routine(ui.theme());
         let item_c = state.ids.items.clone();
         let mut item_iter = item_c.iter().enumerate();
         let mut value_iter = self.values.iter();
-        let mut num_closed = state.num_closed.clone();
+
+        // Pausing holds the time at `paused_at`; resuming moves the start on by the time paused.
+        let now = self.clock.now();
+        if self.restart {
+            state.update(|state| {
+                             state.start = now;
+                             state.paused_at = state.paused_at.map(|_| now);
+                             state.done = false;
+                         });
+        }
+        match (self.paused, state.paused_at) {
+            (true, None) => state.update(|state| state.paused_at = Some(now)),
+            (false, Some(paused_at)) => {
+                let held = if now > paused_at {
+                    now - paused_at
+                } else {
+                    Duration::new(0, 0)
+                };
+                state.update(|state| {
+                                 state.start += held;
+                                 state.paused_at = None;
+                             });
+            }
+            _ => {}
+        }
+        let at = state.paused_at.unwrap_or(now);
+        let elapsed = if at > state.start {
+            at - state.start
+        } else {
+            Duration::new(0, 0)
+        };
+        let frame = if self.skip || state.done {
+            u16::max_value()
+        } else {
+            (secs(elapsed) * NOMINAL_FPS).min(u16::max_value() as f64) as u16
+        };
+        // Giving out takes a turn per card actually given out, plus one to settle.
+        let give_len = self.give_out.as_ref().map_or(0, |g| g.len());
+        let num_closed = num_closed_at(frame, close_frame_rate, len, give_len);
         let step_state =
-            if state.frame <= close_frame_rate {
+            if frame <= close_frame_rate {
                 AniState::Waitthen
-            } else if state.frame <= close_frame_rate * (self.values.len() + 1) as u16 {
-                AniState::Keep(state.frame % close_frame_rate)
-            } else if state.frame <= close_frame_rate * (self.values.len() + 2) as u16 {
+            } else if frame <= close_frame_rate * (self.values.len() + 1) as u16 {
+                AniState::Keep(frame % close_frame_rate)
+            } else if frame <= close_frame_rate * (self.values.len() + 2) as u16 {
                 AniState::Backview
-            } else if state.frame <= close_frame_rate * (self.values.len() + 3) as u16 {
-                AniState::Shuffle(state.frame % close_frame_rate)
-            } else if state.frame <= close_frame_rate * (self.values.len() * 2 + 4) as u16 {
-                if let Some(_) = self.give_out {
-                    AniState::Giveout(state.frame % close_frame_rate)
-                } else {
-                    AniState::Reset
-                }
+            } else if frame <= close_frame_rate * (self.values.len() + 3) as u16 {
+                AniState::Shuffle(frame % close_frame_rate)
+            } else if give_len > 0 &&
+                      frame <= close_frame_rate * (self.values.len() + give_len + 4) as u16 {
+                AniState::Giveout(frame % close_frame_rate)
             } else {
                 AniState::Reset
             };
@@ -153,10 +349,11 @@ impl<'a, T, W> Widget for Shuffle<'a, T, W>
                     let _widget = (*self.widget_closure)(_value.clone().clone());
                     render_movable(k,
                                    len,
-                                   state.frame.clone() - close_frame_rate,
+                                   frame - close_frame_rate,
                                    close_frame_rate,
                                    _step,
                                    num_closed,
+                                   easing,
                                    image_dim,
                                    id,
                                    _widget,
@@ -164,9 +361,41 @@ impl<'a, T, W> Widget for Shuffle<'a, T, W>
                                    _sym.clone().clone(),
                                    ui);
                 }
-                let sign: i8 = -1;
-                if (_step == 0) & (num_closed < len as i8) & (state.frame != 0) {
-                    num_closed = num_closed + sign * (-1);
+            }
+            AniState::Backview |
+            AniState::Shuffle(_) if routine != Routine::Wobble => {
+                let into = frame - close_frame_rate * (len as u16 + 1);
+                let p = into as f64 / (close_frame_rate as f64 * 2.0);
+                // Positions are taken from the middle of the pile at the left of the widget.
+                let x = rect.left() + image_dim[0] / 2.0;
+                let y = rect.y();
+                if let Routine::Riffle = routine {
+                    let split = image_dim[0] * 0.5 * (PI * p).sin();
+                    self.back_card
+                        .w_h(image_dim[0], image_dim[1])
+                        .x_y(x, y)
+                        .set(state.ids.backs[0], ui);
+                    self.back_card
+                        .w_h(image_dim[0], image_dim[1])
+                        .x_y(x + split, y)
+                        .set(state.ids.backs[1], ui);
+                    // A card drops from each half in turn onto the growing pile between them.
+                    let lift = if (into / 4) % 2 == 0 { 0.1 } else { 0.05 };
+                    self.back_card
+                        .w_h(image_dim[0], image_dim[1])
+                        .x_y(x + split * 0.5, y + image_dim[1] * lift * (PI * p).sin())
+                        .set(state.ids.backs[2], ui);
+                } else {
+                    let count = len.max(1).min(FAN_CARDS);
+                    let spread = image_dim[0] * 0.3 * (PI * p).sin();
+                    let middle = (count as f64 - 1.0) / 2.0;
+                    for (i, &back_id) in state.ids.backs.iter().take(count).enumerate() {
+                        let off = i as f64 - middle;
+                        self.back_card
+                            .w_h(image_dim[0], image_dim[1])
+                            .x_y(x + i as f64 * spread, y - off * off * spread * 0.1)
+                            .set(back_id, ui);
+                    }
                 }
             }
             AniState::Backview => {
@@ -187,6 +416,49 @@ impl<'a, T, W> Widget for Shuffle<'a, T, W>
                         .set(state.ids.backview2, ui)
                 }
             }
+            AniState::Giveout(_step) if !self.seats.is_empty() => {
+                let give_out = self.give_out.clone().unwrap();
+                let dealing = ((frame - close_frame_rate * (len as u16 + 3)) /
+                               close_frame_rate) as usize;
+                let t = easing.apply(_step as f64 / close_frame_rate as f64);
+                let pile = [rect.left() + image_dim[0] / 2.0, rect.y()];
+                if dealing + 1 < give_out.len() {
+                    self.back_card
+                        .w_h(image_dim[0], image_dim[1])
+                        .x_y(pile[0], pile[1])
+                        .set(state.ids.backview, ui);
+                }
+                // Cards already dealt sit at their seats, the one being dealt is on its way.
+                let mut at_seat = vec![0; self.seats.len()];
+                let dealt = give_out.iter().enumerate().take((dealing + 1).min(len));
+                for (i, &card) in dealt {
+                    let seat_i = self.seat_for(i);
+                    let seat = self.seats[seat_i];
+                    let target = seat.target.xy(ui).unwrap_or(pile);
+                    let target = [target[0] + at_seat[seat_i] as f64 * image_dim[0] * SEAT_OVERLAP,
+                                  target[1]];
+                    at_seat[seat_i] += 1;
+                    let xy = if i == dealing {
+                        pile.lerp(&target, t)
+                    } else {
+                        target
+                    };
+                    match (seat.face_up, self.values.get(card)) {
+                        (true, Some(value)) => {
+                            (*self.widget_closure)(value.clone())
+                                .w_h(image_dim[0], image_dim[1])
+                                .x_y(xy[0], xy[1])
+                                .set(state.ids.items[i], ui);
+                        }
+                        _ => {
+                            self.back_card
+                                .w_h(image_dim[0], image_dim[1])
+                                .x_y(xy[0], xy[1])
+                                .set(state.ids.seat_backs[i], ui);
+                        }
+                    }
+                }
+            }
             AniState::Giveout(_step) => {
                 let give_out_c = self.give_out.clone().unwrap();
                 let mut give_out_iter = give_out_c.iter();
@@ -196,12 +468,13 @@ impl<'a, T, W> Widget for Shuffle<'a, T, W>
                         let k = _i as f64;
                         let _widget = (*self.widget_closure)(_value.clone().clone());
                         render_movable(k,
-                                       len,
-                                       state.frame.clone() -
+                                       give_len,
+                                       frame -
                                        close_frame_rate * (self.values.len() as u16 + 3),
                                        close_frame_rate,
                                        _step,
                                        num_closed,
+                                       easing,
                                        image_dim,
                                        id,
                                        _widget,
@@ -210,28 +483,60 @@ impl<'a, T, W> Widget for Shuffle<'a, T, W>
                                        ui);
                     }
                 }
-                let sign: i8 = 1;
-                if (_step == 0) & (num_closed > 0) & (state.frame != 0) {
-                    num_closed = num_closed + sign * (-1);
-                }
             }
             AniState::Reset => {}
         }
 
-        state.update(|state| state.num_closed = num_closed);
-        state.update(|state| state.frame += 1);
-
-
-        if let AniState::Reset = step_state {
-            state.update(|state| {
-                             state.frame = 0;
-                             state.num_closed = 0;
-                         });
-            false
-        } else {
-            true
+        let end = close_frame_rate as f64 *
+                  if give_len > 0 {
+                      (len + give_len + 4) as f64
+                  } else {
+                      (len + 3) as f64
+                  };
+        let phase = match step_state {
+            AniState::Waitthen => Phase::Showing,
+            AniState::Keep(_) => Phase::Collecting,
+            AniState::Backview | AniState::Shuffle(_) => Phase::Shuffling,
+            AniState::Giveout(_) => {
+                let dealt = (frame - close_frame_rate * (len as u16 + 3)) / close_frame_rate;
+                Phase::Dealing((dealt as usize).min(give_len - 1))
+            }
+            AniState::Reset => Phase::Done,
+        };
+        if phase == Phase::Done && !state.done {
+            state.update(|state| state.done = true);
         }
+        ShuffleEvent {
+            progress: if phase == Phase::Done || end <= 0.0 {
+                1.0
+            } else {
+                (frame as f64 / end).min(1.0)
+            },
+            phase: phase,
+            paused: self.paused,
+        }
+    }
+}
+/// The number of cards that have slid behind their neighbour by `frame`.
+///
+/// One card closes at every multiple of `close_frame_rate` during `Keep`, and one opens again at
+/// every multiple during `Giveout`, where only the `give_len` cards given out are counted.
+/// Working this out from the frame, rather than counting as the frames go by, keeps it right
+/// when the clock skips frames.
+fn num_closed_at(frame: u16, close_frame_rate: u16, len: usize, give_len: usize) -> i8 {
+    if close_frame_rate == 0 || frame == 0 {
+        return 0;
     }
+    let (len, give_len) = (len as i64, give_len as i64);
+    // Multiples of close_frame_rate strictly before this frame.
+    let passed = ((frame - 1) / close_frame_rate) as i64;
+    let closed = if passed >= len + 3 {
+        give_len
+    } else {
+        (passed.min(len + 1) - 1).max(0)
+    };
+    let opened = (passed.min(len + give_len + 4) - (len + 3)).max(0);
+    (closed - opened).max(0) as i8
 }
 enum Direction {
     Right,
@@ -243,19 +548,21 @@ fn render_movable<T: Widget>(k: f64,
                              close_frame_rate: u16,
                              _step: u16,
                              num_closed: i8,
+                             easing: Easing,
                              image_dim: [f64; 2],
                              id: widget::Id,
                              _widget: T,
                              direction: Direction,
                              _sym: widget::id::Id,
                              ui: &mut UiCell) {
+    let t = easing.apply(_step as f64 / close_frame_rate as f64);
     let (sign, image_to_move, less_than_show): (f64, f64, f64) = if let Direction::Left =
         direction {
-        (image_dim[0] * (1.0 / close_frame_rate as f64) * _step as f64,
+        (image_dim[0] * t,
          (len as f64 - 1.0) - (frame as f64 / close_frame_rate as f64).floor() as f64,
          (len as i8 - 1 - num_closed) as f64)
     } else {
-        (image_dim[0] - (1.0 / close_frame_rate as f64) * _step as f64,
+        (image_dim[0] * (1.0 - t),
          (frame as f64 / close_frame_rate as f64).floor(),
          (len as i8 - num_closed) as f64)
     };
@@ -274,3 +581,47 @@ fn render_movable<T: Widget>(k: f64,
 
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::num_closed_at;
+
+    /// `num_closed_at` at the first and last frame of every turn of `rate` frames.
+    fn turns(rate: u16, len: usize, give_len: usize, n: u16) -> Vec<(i8, i8)> {
+        (0..n)
+            .map(|turn| {
+                     (num_closed_at(turn * rate + 1, rate, len, give_len),
+                      num_closed_at((turn + 1) * rate, rate, len, give_len))
+                 })
+            .collect()
+    }
+
+    #[test]
+    fn cards_close_while_collecting_and_open_while_giving_out() {
+        assert_eq!(num_closed_at(0, 10, 3, 2), 0);
+        // Showing, three turns of collecting, backview, shuffling, then giving out two cards
+        // and settling.
+        assert_eq!(turns(10, 3, 2, 10),
+                   vec![(0, 0), (0, 0), (1, 1), (2, 2), (3, 3), (3, 3), (2, 2), (1, 1), (0, 0),
+                        (0, 0)]);
+    }
+
+    #[test]
+    fn nothing_opens_without_a_give_out() {
+        assert_eq!(turns(10, 3, 0, 8),
+                   vec![(0, 0), (0, 0), (1, 1), (2, 2), (3, 3), (3, 3), (0, 0), (0, 0)]);
+        assert_eq!(num_closed_at(u16::max_value(), 10, 3, 0), 0);
+    }
+
+    #[test]
+    fn any_frame_gives_a_count_in_range() {
+        for frame in 0..200 {
+            let closed = num_closed_at(frame, 10, 5, 3);
+            assert!(closed >= 0 && closed <= 5, "frame {}", frame);
+        }
+        assert_eq!(num_closed_at(35, 10, 5, 3), 2);
+        assert_eq!(num_closed_at(81, 10, 5, 3), 3);
+        assert_eq!(num_closed_at(u16::max_value(), 10, 5, 3), 0);
+        assert_eq!(num_closed_at(35, 0, 5, 3), 0);
+    }
+}
@@ -1,12 +1,14 @@
 use conrod_core::{self, widget, Positionable, Widget, Sizeable, Labelable, Colorable};
 use custom_widget::pad_text_button;
 
+/// The labels of a `TableList`. `locale::Locale` implements this by looking up `table_list.*`
+/// keys, so a `Locale` can be passed as the `appdata`.
 pub trait TableListTexts {
-    fn text_ready(&self) -> &'static str;
-    fn text_leave(&self) -> &'static str;
-    fn text_join(&self) -> &'static str;
-    fn text_playergame(&self) -> &'static str;
-    fn text_changeto(&self) -> &'static str;
+    fn text_ready(&self) -> &str;
+    fn text_leave(&self) -> &str;
+    fn text_join(&self) -> &str;
+    fn text_playergame(&self) -> &str;
+    fn text_changeto(&self) -> &str;
 }
 /// The type upon which we'll implement the `Widget` trait.
 #[derive(WidgetCommon)]
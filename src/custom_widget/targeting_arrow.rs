@@ -0,0 +1,241 @@
+//! Choosing a target for a spell or an attack by dragging an arrow out of a card.
+//!
+//! Set a `TargetingArrow` for the card after the widgets it can point at. Dragging the card
+//! draws a curved arrow from it to the mouse, and the widget under the arrow's tip is outlined
+//! when `is_valid_target` takes it. Letting go over it targets it; letting go anywhere else,
+//! a right click or Escape cancels.
+use conrod_core::{widget, color, event, input, Color, Colorable, Point, Positionable, Rect,
+                  Scalar, Widget};
+use tween::quadratic_bezier;
+
+/// How choosing a target ended.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TargetEvent {
+    Targeted(widget::Id),
+    Cancelled,
+}
+
+/// Draws the arrow while the source is dragged. The widget's own position and size are not
+/// used; the arrow runs from the middle of the source to the mouse.
+#[derive(WidgetCommon)]
+pub struct TargetingArrow {
+    /// An object that handles some of the dirty work of rendering a GUI. We don't
+    /// really have to worry about it.
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    pub source: widget::Id,
+    is_valid_target: Box<Fn(widget::Id) -> bool>,
+    /// See the Style struct below.
+    style: Style,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
+pub struct Style {
+    /// Color of the arrow while it points at nothing it can target.
+    #[conrod(default = "color::ORANGE")]
+    pub color: Option<Color>,
+    /// Color of the arrow and of the outline of the target under its tip.
+    #[conrod(default = "color::LIGHT_GREEN")]
+    pub target_color: Option<Color>,
+    #[conrod(default = "6.0")]
+    pub thickness: Option<Scalar>,
+    /// How far the middle of the arrow bows out, as a fraction of its length.
+    #[conrod(default = "0.25")]
+    pub bend: Option<f64>,
+    /// The number of straight pieces the curve is drawn with.
+    #[conrod(default = "24")]
+    pub segments: Option<usize>,
+    /// The length and width of the arrow head.
+    #[conrod(default = "[24.0, 20.0]")]
+    pub head_dim: Option<[f64; 2]>,
+}
+
+widget_ids! {
+    struct Ids {
+        path,
+        head,
+        highlight,
+    }
+}
+
+/// Represents the unique, cached state for our TargetingArrow widget.
+pub struct State {
+    ids: Ids,
+    /// Whether the arrow is out.
+    aiming: bool,
+    /// Whether the arrow was cancelled while the left button is still down. Dragging the source
+    /// doesn't bring it back until the button is let go.
+    cancelled: bool,
+}
+
+impl TargetingArrow {
+    /// Create a button context to be built upon.
+    pub fn new(source: widget::Id, is_valid_target: Box<Fn(widget::Id) -> bool>) -> Self {
+        TargetingArrow {
+            source: source,
+            is_valid_target: is_valid_target,
+            common: widget::CommonBuilder::default(),
+            style: Style::default(),
+        }
+    }
+    builder_methods!{
+        pub target_color { style.target_color = Some(Color) }
+        pub thickness { style.thickness = Some(Scalar) }
+        pub bend { style.bend = Some(f64) }
+        pub segments { style.segments = Some(usize) }
+        pub head_dim { style.head_dim = Some([f64; 2]) }
+    }
+}
+
+/// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
+/// documentation for more details.
+impl Widget for TargetingArrow {
+    /// The State struct that we defined above.
+    type State = State;
+    /// The Style struct that we defined using the `widget_style!` macro.
+    type Style = Style;
+    /// The event produced by instantiating the widget.
+    ///
+    /// `Some` on the update the arrow is let go or cancelled.
+    type Event = Option<TargetEvent>;
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State {
+            ids: Ids::new(id_gen),
+            aiming: false,
+            cancelled: false,
+        }
+    }
+
+    fn style(&self) -> Self::Style {
+        self.style.clone()
+    }
+
+    /// Update the state of the button by handling any input that has occurred since the last
+    /// update.
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { id, state, ui, .. } = args;
+        let from = match ui.xy_of(self.source) {
+            Some(from) => from,
+            None => {
+                if state.aiming {
+                    state.update(|state| state.aiming = false);
+                    return Some(TargetEvent::Cancelled);
+                }
+                return None;
+            }
+        };
+        let released = ui.global_input().current.mouse.buttons.left().is_up();
+        if state.cancelled {
+            if released {
+                state.update(|state| state.cancelled = false);
+            }
+            return None;
+        }
+        let mut aiming = state.aiming ||
+                         ui.widget_input(self.source).drags().left().next().is_some();
+        if !aiming {
+            return None;
+        }
+
+        let tip = ui.global_input().current.mouse.xy;
+        let cancelled = ui.global_input().events().ui().any(|e| match *e {
+            event::Ui::Press(_, press) => {
+                press.mouse().map_or(false, |m| m.button == input::MouseButton::Right) ||
+                press.key().map_or(false, |k| k.key == input::Key::Escape)
+            }
+            _ => false,
+        });
+
+        // The smallest valid widget under the tip, so a card is picked over the zone it is in.
+        let own = [id, state.ids.path, state.ids.head, state.ids.highlight];
+        let target = ui.updated_widgets()
+            .iter()
+            .cloned()
+            .filter(|w| !own.contains(w) && *w != ui.window)
+            .filter_map(|w| ui.rect_of(w).map(|r| (w, r)))
+            .filter(|&(w, r)| r.is_over(tip) && (*self.is_valid_target)(w))
+            .min_by(|a, b| area(a.1).partial_cmp(&area(b.1)).unwrap())
+            .map(|(w, _)| w);
+
+        let mut event = None;
+        if cancelled {
+            aiming = false;
+            event = Some(TargetEvent::Cancelled);
+            if !released {
+                state.update(|state| state.cancelled = true);
+            }
+        } else if released {
+            aiming = false;
+            event = Some(target.map_or(TargetEvent::Cancelled, TargetEvent::Targeted));
+        }
+        if aiming != state.aiming {
+            state.update(|state| state.aiming = aiming);
+        }
+        if !aiming {
+            return event;
+        }
+
+        let color = if target.is_some() {
+            self.style.target_color(&ui.theme)
+        } else {
+            self.style.color(&ui.theme)
+        };
+        let thickness = self.style.thickness(&ui.theme);
+        if let Some(rect) = target.and_then(|t| ui.rect_of(t)) {
+            widget::Rectangle::outline_styled(rect.dim(),
+                                              widget::line::Style::solid()
+                                                  .color(color)
+                                                  .thickness(thickness))
+                .xy(rect.xy())
+                .parent(id)
+                .graphics_for(id)
+                .set(state.ids.highlight, ui);
+        }
+
+        // Bow the curve out to the left of the way it is going, and stop it at the head.
+        let head_dim = self.style.head_dim(&ui.theme);
+        let (dx, dy) = (tip[0] - from[0], tip[1] - from[1]);
+        let bend = self.style.bend(&ui.theme);
+        let control = [(from[0] + tip[0]) / 2.0 - dy * bend, (from[1] + tip[1]) / 2.0 + dx * bend];
+        let segments = self.style.segments(&ui.theme).max(1);
+        let mut points = (0..segments + 1)
+            .map(|i| quadratic_bezier(from, control, tip, i as f64 / segments as f64))
+            .collect::<Vec<Point>>();
+        let (ux, uy) = direction(control, tip);
+        let base = [tip[0] - ux * head_dim[0], tip[1] - uy * head_dim[0]];
+        points.pop();
+        points.push(base);
+        widget::PointPath::abs(points)
+            .color(color)
+            .thickness(thickness)
+            .parent(id)
+            .graphics_for(id)
+            .set(state.ids.path, ui);
+        let half_w = head_dim[1] / 2.0;
+        let head = vec![tip,
+                        [base[0] - uy * half_w, base[1] + ux * half_w],
+                        [base[0] + uy * half_w, base[1] - ux * half_w]];
+        widget::Polygon::abs_fill(head)
+            .color(color)
+            .parent(id)
+            .graphics_for(id)
+            .set(state.ids.head, ui);
+        event
+    }
+}
+
+impl Colorable for TargetingArrow {
+    builder_method!(color { style.color = Some(Color) });
+}
+
+fn area(r: Rect) -> f64 {
+    r.w() * r.h()
+}
+
+/// The unit vector pointing from `a` to `b`, or straight up if they are the same point.
+fn direction(a: Point, b: Point) -> (f64, f64) {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    let len = (dx * dx + dy * dy).sqrt();
+    if len > 0.0 { (dx / len, dy / len) } else { (0.0, 1.0) }
+}
@@ -0,0 +1,400 @@
+//! Tooltips shown after hovering a widget for a while, with a glossary of keywords.
+//!
+//! The app keeps one `Tooltips` and sets one `TooltipOverlay` over it after every other widget.
+//! Widgets built with `.tooltip(&tooltips)` ask for their tooltip while they are hovered; any
+//! other widget can do the same with `Tooltips::request_if_hovered`. Tooltip text is `RichText`
+//! markup. Words that have a glossary entry are highlighted, and hovering one opens its entry
+//! next to the tooltip, which can in turn name other keywords.
+use conrod_core::{widget, color, text, Color, Colorable, FontSize, Point, Positionable, Rect,
+                  Scalar, Sizeable, Ui, Widget};
+use clock::{since, Clock, SYSTEM_CLOCK};
+use custom_widget::rich_text::{IconMap, RichText};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// The glossary and the tooltip asked for during this update, shared by the widgets that ask
+/// for tooltips and the `TooltipOverlay`.
+#[derive(Clone, Debug, Default)]
+pub struct Tooltips {
+    /// Glossary entries by keyword, in lower case.
+    glossary: HashMap<String, String>,
+    /// The widget hovered during this update and its tooltip.
+    hovered: RefCell<Option<(widget::Id, String)>>,
+}
+
+impl Tooltips {
+    pub fn new() -> Self {
+        Tooltips::default()
+    }
+    /// Explain `keyword` with the markup `entry` wherever it appears in a tooltip. Keywords are
+    /// single words and are matched regardless of case.
+    pub fn insert(&mut self, keyword: &str, entry: &str) {
+        self.glossary.insert(keyword.to_lowercase(), entry.to_owned());
+    }
+    /// The glossary entry for `word`, if it is a keyword.
+    pub fn entry(&self, word: &str) -> Option<&str> {
+        self.glossary.get(&word.to_lowercase()).map(|e| e.as_str())
+    }
+    /// Report that the mouse is over the widget `source`, whose tooltip is `text`. Call on
+    /// every update while it is; once it has been hovered for the overlay's `hover_delay`, the
+    /// tooltip is shown until the mouse leaves both the widget and the tooltip.
+    pub fn request(&self, source: widget::Id, text: &str) {
+        *self.hovered.borrow_mut() = Some((source, text.to_owned()));
+    }
+    /// `request` if the mouse is over the widget `source`.
+    pub fn request_if_hovered(&self, ui: &Ui, source: widget::Id, text: &str) {
+        if ui.widget_input(source).mouse().is_some() {
+            self.request(source, text);
+        }
+    }
+}
+
+/// Draws the tooltip asked for through a `Tooltips`, below or above the widget it belongs to,
+/// and the glossary entries of the keywords hovered inside it. Everything is kept inside the
+/// window where it fits.
+#[derive(WidgetCommon)]
+pub struct TooltipOverlay<'a> {
+    /// An object that handles some of the dirty work of rendering a GUI. We don't
+    /// really have to worry about it.
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    pub tooltips: &'a Tooltips,
+    pub icons: Option<&'a IconMap>,
+    pub clock: &'a Clock,
+    /// See the Style struct below.
+    style: Style,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
+pub struct Style {
+    /// Color of the tooltip box.
+    #[conrod(default = "theme.background_color")]
+    pub color: Option<Color>,
+    #[conrod(default = "theme.border_width")]
+    pub border: Option<Scalar>,
+    #[conrod(default = "theme.border_color")]
+    pub border_color: Option<Color>,
+    #[conrod(default = "theme.label_color")]
+    pub text_color: Option<Color>,
+    /// Color of the keywords that have a glossary entry.
+    #[conrod(default = "color::LIGHT_BLUE")]
+    pub keyword_color: Option<Color>,
+    #[conrod(default = "theme.font_size_small")]
+    pub font_size: Option<FontSize>,
+    /// Specify a unique font for the text.
+    #[conrod(default = "theme.font_id")]
+    pub font_id: Option<Option<text::font::Id>>,
+    /// How long a widget has to be hovered before its tooltip is shown.
+    #[conrod(default = "Duration::from_millis(400)")]
+    pub hover_delay: Option<Duration>,
+    /// How long the mouse can be off a tooltip or keyword before what it opened closes, so it
+    /// can cross the gap between a widget and its tooltip.
+    #[conrod(default = "Duration::from_millis(300)")]
+    pub close_delay: Option<Duration>,
+    /// The widest a tooltip can be before its text is wrapped.
+    #[conrod(default = "250.0")]
+    pub max_w: Option<Scalar>,
+    /// The space between the text and the edge of the box.
+    #[conrod(default = "6.0")]
+    pub padding: Option<Scalar>,
+    /// The gap kept between a tooltip, its widget and the window edges.
+    #[conrod(default = "4.0")]
+    pub margin: Option<Scalar>,
+    /// The most boxes open at once, counting the tooltip and the glossary entries opened from
+    /// it.
+    #[conrod(default = "3")]
+    pub max_depth: Option<usize>,
+}
+
+widget_ids! {
+    struct Ids {
+        boxes[],
+        texts[],
+    }
+}
+
+/// Represents the unique, cached state for our TooltipOverlay widget.
+pub struct State {
+    ids: Ids,
+    /// The widget being hovered, its tooltip and since when.
+    pending: Option<(widget::Id, String, Instant)>,
+    /// The widget whose tooltip is shown and the tooltip.
+    open: Option<(widget::Id, String)>,
+    /// The keywords whose entries are open, one per box after the tooltip.
+    keywords: Vec<String>,
+    /// Since when the mouse has been off something that keeps a box open.
+    leaving: Option<Instant>,
+}
+
+impl<'a> TooltipOverlay<'a> {
+    /// Create a button context to be built upon.
+    pub fn new(tooltips: &'a Tooltips) -> Self {
+        TooltipOverlay {
+            tooltips: tooltips,
+            icons: None,
+            clock: &SYSTEM_CLOCK,
+            common: widget::CommonBuilder::default(),
+            style: Style::default(),
+        }
+    }
+    /// Icons that `{name}` markup in tooltips refers to.
+    pub fn icons(mut self, icons: &'a IconMap) -> Self {
+        self.icons = Some(icons);
+        self
+    }
+    /// Read the time from `clock` instead of the system clock.
+    pub fn clock(mut self, clock: &'a Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+    /// Specify the font used for the text.
+    pub fn font_id(mut self, font_id: text::font::Id) -> Self {
+        self.style.font_id = Some(Some(font_id));
+        self
+    }
+    builder_methods!{
+        pub border { style.border = Some(Scalar) }
+        pub border_color { style.border_color = Some(Color) }
+        pub text_color { style.text_color = Some(Color) }
+        pub keyword_color { style.keyword_color = Some(Color) }
+        pub font_size { style.font_size = Some(FontSize) }
+        pub hover_delay { style.hover_delay = Some(Duration) }
+        pub close_delay { style.close_delay = Some(Duration) }
+        pub max_w { style.max_w = Some(Scalar) }
+        pub padding { style.padding = Some(Scalar) }
+        pub margin { style.margin = Some(Scalar) }
+        pub max_depth { style.max_depth = Some(usize) }
+    }
+}
+
+/// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
+/// documentation for more details.
+impl<'a> Widget for TooltipOverlay<'a> {
+    /// The State struct that we defined above.
+    type State = State;
+    /// The Style struct that we defined using the `widget_style!` macro.
+    type Style = Style;
+    /// The event produced by instantiating the widget.
+    ///
+    /// The widget whose tooltip is shown after this update, if any.
+    type Event = Option<widget::Id>;
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State {
+            ids: Ids::new(id_gen),
+            pending: None,
+            open: None,
+            keywords: vec![],
+            leaving: None,
+        }
+    }
+
+    fn style(&self) -> Self::Style {
+        self.style.clone()
+    }
+
+    /// Update the state of the button by handling any input that has occurred since the last
+    /// update.
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { id, state, ui, .. } = args;
+        let tooltips = self.tooltips;
+        let max_depth = self.style.max_depth(&ui.theme).max(1);
+        if state.ids.boxes.len() < max_depth {
+            let id_gen = &mut ui.widget_id_generator();
+            state.update(|state| {
+                             state.ids.boxes.resize(max_depth, id_gen);
+                             state.ids.texts.resize(max_depth, id_gen);
+                         });
+        }
+        let (boxes, texts) = (state.ids.boxes.clone(), state.ids.texts.clone());
+        let hovered = |ui: &Ui, depth: usize| {
+            ui.widget_input(boxes[depth]).mouse().is_some() ||
+            ui.widget_input(texts[depth]).mouse().is_some()
+        };
+        let over_tooltip = state.open.is_some() && (0..max_depth).any(|d| hovered(ui, d));
+
+        let mut open = state.open.clone();
+        let mut pending = state.pending.clone();
+        let mut keywords = state.keywords.clone();
+        keywords.truncate(max_depth - 1);
+        // Boxes only close once the mouse has been off them for `close_delay`.
+        let leaving = state.leaving;
+        let close_delay = self.style.close_delay(&ui.theme);
+        let grace_over = since(self.clock, leaving.unwrap_or_else(|| self.clock.now())) >=
+                         close_delay;
+        let mut closing = false;
+        let requested = tooltips.hovered.borrow_mut().take();
+        match requested {
+            Some((source, text)) => {
+                let showing = open.as_ref().map_or(false, |&(s, ref t)| s == source && *t == text);
+                if !showing {
+                    open = None;
+                    keywords.clear();
+                    let start = match pending {
+                        Some((s, ref t, start)) if s == source && *t == text => start,
+                        _ => self.clock.now(),
+                    };
+                    if since(self.clock, start) >= self.style.hover_delay(&ui.theme) {
+                        open = Some((source, text));
+                        pending = None;
+                    } else {
+                        pending = Some((source, text, start));
+                    }
+                }
+            }
+            None => {
+                pending = None;
+                if open.is_some() && !over_tooltip {
+                    closing = true;
+                    if grace_over {
+                        open = None;
+                        keywords.clear();
+                    }
+                }
+            }
+        }
+
+        let source = open.as_ref().map(|&(s, _)| s);
+        let mut anchor = source.and_then(|s| ui.rect_of(s));
+        let mut markup = open.as_ref().map(|&(_, ref t)| t.clone());
+        let window = ui.rect_of(ui.window).unwrap_or(Rect::from_xy_dim([0.0, 0.0], [0.0, 0.0]));
+        let font_size = self.style.font_size(&ui.theme);
+        let padding = self.style.padding(&ui.theme);
+        let margin = self.style.margin(&ui.theme);
+        let max_w = self.style.max_w(&ui.theme);
+        let keyword_color = self.style.keyword_color(&ui.theme);
+        let mut depth = 0;
+        while let (Some(text), Some(next_to)) = (markup.take(), anchor) {
+            let marked = mark_keywords(&text, tooltips, keyword_color);
+            let rich = RichText::new(&marked)
+                .font_size(font_size)
+                .color(self.style.text_color(&ui.theme))
+                .and_then(self.style.font_id(&ui.theme), RichText::font_id);
+            let rich = match self.icons {
+                Some(icons) => rich.icons(icons),
+                None => rich,
+            };
+            let size = rich.size(ui, max_w - 2.0 * padding);
+            let dim = [size[0] + 2.0 * padding, size[1] + 2.0 * padding];
+            let xy = place(dim, next_to, window, margin, depth > 0);
+            widget::BorderedRectangle::new(dim)
+                .color(self.style.color(&ui.theme))
+                .border(self.style.border(&ui.theme))
+                .border_color(self.style.border_color(&ui.theme))
+                .x_y(xy[0], xy[1])
+                .parent(id)
+                .set(boxes[depth], ui);
+            let word = rich.w_h(size[0], size[1])
+                .middle_of(boxes[depth])
+                .parent(boxes[depth])
+                .set(texts[depth], ui);
+
+            // An entry stays open while its keyword or any box opened from it is hovered.
+            let keyword = word.filter(|w| tooltips.entry(w).is_some()).map(|w| w.to_lowercase());
+            match keyword {
+                Some(k) => {
+                    if depth + 1 < max_depth && keywords.get(depth) != Some(&k) {
+                        keywords.truncate(depth);
+                        keywords.push(k);
+                    }
+                }
+                None => {
+                    if keywords.len() > depth && !(depth + 1..max_depth).any(|d| hovered(ui, d)) {
+                        closing = true;
+                        if grace_over {
+                            keywords.truncate(depth);
+                        }
+                    }
+                }
+            }
+            markup = keywords.get(depth).and_then(|k| tooltips.entry(k)).map(|e| e.to_owned());
+            anchor = Some(Rect::from_xy_dim(xy, dim));
+            depth += 1;
+        }
+        keywords.truncate(depth.saturating_sub(1));
+        let leaving = if closing {
+            leaving.or_else(|| Some(self.clock.now()))
+        } else {
+            None
+        };
+
+        if open != state.open || pending != state.pending || keywords != state.keywords ||
+           leaving != state.leaving {
+            state.update(|state| {
+                             state.open = open;
+                             state.pending = pending;
+                             state.keywords = keywords;
+                             state.leaving = leaving;
+                         });
+        }
+        source
+    }
+}
+
+impl<'a> Colorable for TooltipOverlay<'a> {
+    builder_method!(color { style.color = Some(Color) });
+}
+
+/// `markup` with every glossary keyword in `keyword_color`, leaving icons and tags alone.
+fn mark_keywords(markup: &str, tooltips: &Tooltips, keyword_color: Color) -> String {
+    let c = keyword_color.to_rgb();
+    let byte = |v: f32| (v * 255.0).round() as u8;
+    let open = format!("[color=#{:02x}{:02x}{:02x}]", byte(c.0), byte(c.1), byte(c.2));
+    let mut out = String::with_capacity(markup.len());
+    let mut rest = markup;
+    while let Some(c) = rest.chars().next() {
+        let len = if rest.starts_with("{{") || rest.starts_with("[[") {
+            2
+        } else if c == '{' || c == '[' {
+            let close = if c == '{' { '}' } else { ']' };
+            rest.find(close).map_or(1, |end| end + 1)
+        } else if c.is_alphanumeric() {
+            let len = rest.find(|c: char| !c.is_alphanumeric()).unwrap_or(rest.len());
+            if tooltips.entry(&rest[..len]).is_some() {
+                out.push_str(&open);
+                out.push_str(&rest[..len]);
+                out.push_str("[/color]");
+                rest = &rest[len..];
+                continue;
+            }
+            len
+        } else {
+            c.len_utf8()
+        };
+        out.push_str(&rest[..len]);
+        rest = &rest[len..];
+    }
+    out
+}
+
+/// Where to put the middle of a box of `dim` next to `anchor`: below it, or above it when
+/// there is more room there. `beside` puts it to the right or left instead, lined up with the
+/// top. The box is kept inside the window where it fits.
+fn place(dim: [f64; 2], anchor: Rect, window: Rect, margin: Scalar, beside: bool) -> Point {
+    let (x, y) = if beside {
+        let x = if window.right() - anchor.right() >= anchor.left() - window.left() {
+            anchor.right() + margin + dim[0] / 2.0
+        } else {
+            anchor.left() - margin - dim[0] / 2.0
+        };
+        (x, anchor.top() - dim[1] / 2.0)
+    } else {
+        let below = anchor.bottom() - window.bottom();
+        let y = if below >= dim[1] + 2.0 * margin || below >= window.top() - anchor.top() {
+            anchor.bottom() - margin - dim[1] / 2.0
+        } else {
+            anchor.top() + margin + dim[1] / 2.0
+        };
+        (anchor.left() + dim[0] / 2.0, y)
+    };
+    // A box bigger than the window is centred on it instead.
+    let clamp = |v: f64, lo: f64, hi: f64| if lo > hi {
+        (lo + hi) / 2.0
+    } else {
+        v.max(lo).min(hi)
+    };
+    let half = [dim[0] / 2.0 + margin, dim[1] / 2.0 + margin];
+    [clamp(x, window.left() + half[0], window.right() - half[0]),
+     clamp(y, window.bottom() + half[1], window.top() - half[1])]
+}
@@ -0,0 +1,274 @@
+//! Showing a card enlarged over everything else.
+//!
+//! The app keeps one `Zoom` and sets one `ZoomOverlay` over it after every other widget. Any
+//! widget can then ask for a card to be shown with `Zoom::show`, or preview it while hovered
+//! with `Zoom::hover`. `Zoom::card` is the same card index that `ArrangeList::blow_up` and
+//! `ItemHistory::overlay_blowup` write, so `&mut zoom.card` can be handed to them directly. A
+//! card they write replaces any hover preview and stays until it is dismissed.
+use conrod_core::{widget, color, event, input, Color, Labelable, Point, Positionable,
+                  Rect, Scalar, Sizeable, Ui, Widget};
+use clock::{since, Clock, SYSTEM_CLOCK};
+use std::time::{Duration, Instant};
+
+/// Which card is zoomed, shared by the widgets that ask for it and the `ZoomOverlay`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Zoom {
+    /// The card shown enlarged, as an index the overlay's widget closure understands.
+    pub card: Option<usize>,
+    /// The widget the card was zoomed from. The card is placed beside it when known.
+    pub source: Option<widget::Id>,
+    /// The card while it is only previewed because it is hovered. A different `card` means
+    /// something else has written it since.
+    preview: Option<usize>,
+    /// The card hovered during this update.
+    hovered: Option<(usize, widget::Id)>,
+}
+
+impl Zoom {
+    pub fn new() -> Self {
+        Zoom::default()
+    }
+    /// Show `card` enlarged until it is dismissed.
+    pub fn show(&mut self, card: usize, source: Option<widget::Id>) {
+        self.card = Some(card);
+        self.source = source;
+        self.preview = None;
+    }
+    pub fn close(&mut self) {
+        self.card = None;
+        self.source = None;
+        self.preview = None;
+    }
+    pub fn is_open(&self) -> bool {
+        self.card.is_some()
+    }
+    /// Report that the mouse is over `card` drawn by widget `source`. Call on every update while
+    /// it is; once it has been hovered for the overlay's `hover_delay`, the card is previewed
+    /// until the mouse leaves.
+    pub fn hover(&mut self, card: usize, source: widget::Id) {
+        self.hovered = Some((card, source));
+    }
+    /// `hover` if the mouse is over the widget `source`.
+    pub fn hover_widget(&mut self, ui: &Ui, card: usize, source: widget::Id) {
+        if ui.widget_input(source).mouse().is_some() {
+            self.hover(card, source);
+        }
+    }
+}
+
+/// Draws the card of a `Zoom` enlarged over a dimmed window, with a close button. Escape or a
+/// click outside the card also closes it. Hover previews are drawn without the dimming or the
+/// close button and go away with the mouse.
+#[derive(WidgetCommon)]
+pub struct ZoomOverlay<'a, W>
+    where W: Widget
+{
+    /// An object that handles some of the dirty work of rendering a GUI. We don't
+    /// really have to worry about it.
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+    pub zoom: &'a mut Zoom,
+    widget_closure: Box<Fn(usize) -> W>,
+    pub clock: &'a Clock,
+    /// See the Style struct below.
+    style: Style,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, WidgetStyle)]
+pub struct Style {
+    /// The size of the enlarged card.
+    #[conrod(default = "[300.0, 420.0]")]
+    pub card_dim: Option<[f64; 2]>,
+    /// The color laid over the window behind the card.
+    #[conrod(default = "color::BLACK.alpha(0.6)")]
+    pub dim_color: Option<Color>,
+    /// How long a card has to be hovered before it is previewed.
+    #[conrod(default = "Duration::from_millis(500)")]
+    pub hover_delay: Option<Duration>,
+    /// The gap kept between the card, its source and the window edges.
+    #[conrod(default = "10.0")]
+    pub margin: Option<Scalar>,
+    #[conrod(default = "24.0")]
+    pub close_size: Option<Scalar>,
+}
+
+widget_ids! {
+    struct Ids {
+        dim,
+        card,
+        close,
+    }
+}
+
+/// Represents the unique, cached state for our ZoomOverlay widget.
+pub struct State {
+    ids: Ids,
+    /// The card being hovered and since when.
+    hover: Option<(usize, widget::Id, Instant)>,
+}
+
+impl<'a, W> ZoomOverlay<'a, W>
+    where W: Widget
+{
+    /// Create a button context to be built upon.
+    pub fn new(zoom: &'a mut Zoom, widget_closure: Box<Fn(usize) -> W>) -> Self {
+        ZoomOverlay {
+            zoom: zoom,
+            widget_closure: widget_closure,
+            clock: &SYSTEM_CLOCK,
+            common: widget::CommonBuilder::default(),
+            style: Style::default(),
+        }
+    }
+    /// Read the time from `clock` instead of the system clock.
+    pub fn clock(mut self, clock: &'a Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+    builder_methods!{
+        pub card_dim { style.card_dim = Some([f64; 2]) }
+        pub dim_color { style.dim_color = Some(Color) }
+        pub hover_delay { style.hover_delay = Some(Duration) }
+        pub margin { style.margin = Some(Scalar) }
+        pub close_size { style.close_size = Some(Scalar) }
+    }
+}
+
+/// A custom Conrod widget must implement the Widget trait. See the **Widget** trait
+/// documentation for more details.
+impl<'a, W> Widget for ZoomOverlay<'a, W>
+    where W: Widget
+{
+    /// The State struct that we defined above.
+    type State = State;
+    /// The Style struct that we defined using the `widget_style!` macro.
+    type Style = Style;
+    /// The event produced by instantiating the widget.
+    ///
+    /// The card shown after this update, if any.
+    type Event = Option<usize>;
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State {
+            ids: Ids::new(id_gen),
+            hover: None,
+        }
+    }
+
+    fn style(&self) -> Self::Style {
+        self.style.clone()
+    }
+
+    /// Update the state of the button by handling any input that has occurred since the last
+    /// update.
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { id, state, ui, .. } = args;
+        let zoom = self.zoom;
+        // A card written straight into `card` while previewing is shown like `Zoom::show`.
+        if zoom.preview.is_some() && zoom.preview != zoom.card {
+            zoom.preview = None;
+            zoom.source = None;
+        }
+
+        // Hover previews.
+        match zoom.hovered.take() {
+            Some((card, source)) => {
+                let previous = state.hover;
+                let start = match previous {
+                    Some((c, s, start)) if c == card && s == source => start,
+                    _ => {
+                        let now = self.clock.now();
+                        state.update(|state| state.hover = Some((card, source, now)));
+                        now
+                    }
+                };
+                let delay = self.style.hover_delay(&ui.theme);
+                let free = zoom.card.is_none() || zoom.preview.is_some();
+                if free && since(self.clock, start) >= delay {
+                    zoom.card = Some(card);
+                    zoom.source = Some(source);
+                    zoom.preview = Some(card);
+                }
+            }
+            None => {
+                if state.hover.is_some() {
+                    state.update(|state| state.hover = None);
+                }
+                if zoom.preview.is_some() {
+                    zoom.close();
+                }
+            }
+        }
+
+        let escape = ui.global_input().events().ui().any(|e| match *e {
+            event::Ui::Press(_, press) => {
+                press.key().map_or(false, |k| k.key == input::Key::Escape)
+            }
+            _ => false,
+        });
+        if escape && zoom.preview.is_none() {
+            zoom.close();
+        }
+        let card = match zoom.card {
+            Some(card) => card,
+            None => return None,
+        };
+
+        let card_dim = self.style.card_dim(&ui.theme);
+        let margin = self.style.margin(&ui.theme);
+        let window = ui.rect_of(ui.window).unwrap_or(Rect::from_xy_dim([0.0, 0.0], card_dim));
+        let source = zoom.source.and_then(|s| ui.rect_of(s));
+        let xy = place(card_dim, source, window, margin);
+        if zoom.preview.is_none() {
+            widget::Rectangle::fill_with(window.dim(), self.style.dim_color(&ui.theme))
+                .xy(window.xy())
+                .parent(id)
+                .set(state.ids.dim, ui);
+        }
+        (*self.widget_closure)(card)
+            .w_h(card_dim[0], card_dim[1])
+            .x_y(xy[0], xy[1])
+            .parent(id)
+            .set(state.ids.card, ui);
+        if zoom.preview.is_some() {
+            return zoom.card;
+        }
+
+        let close_size = self.style.close_size(&ui.theme);
+        let closed = widget::Button::new()
+            .label("x")
+            .w_h(close_size, close_size)
+            .top_right_with_margins_on(state.ids.card, -close_size * 0.5, -close_size * 0.5)
+            .parent(id)
+            .set(state.ids.close, ui)
+            .was_clicked();
+        let outside = ui.widget_input(state.ids.dim).clicks().left().count() > 0;
+        if closed || outside {
+            zoom.close();
+        }
+        zoom.card
+    }
+}
+
+/// Where to put the middle of a card of `dim`: beside `source` on whichever side has more room,
+/// or in the middle of the window. The card is kept inside the window where it fits.
+fn place(dim: [f64; 2], source: Option<Rect>, window: Rect, margin: Scalar) -> Point {
+    let source = match source {
+        Some(source) => source,
+        None => return window.xy(),
+    };
+    let x = if window.right() - source.right() >= source.left() - window.left() {
+        source.right() + margin + dim[0] / 2.0
+    } else {
+        source.left() - margin - dim[0] / 2.0
+    };
+    // A card bigger than the window is centred on it instead.
+    let clamp = |v: f64, lo: f64, hi: f64| if lo > hi {
+        (lo + hi) / 2.0
+    } else {
+        v.max(lo).min(hi)
+    };
+    let half = [dim[0] / 2.0 + margin, dim[1] / 2.0 + margin];
+    [clamp(x, window.left() + half[0], window.right() - half[0]),
+     clamp(source.y(), window.bottom() + half[1], window.top() - half[1])]
+}
@@ -0,0 +1,685 @@
+//! Sharing decklists as text, JSON or a compact deck code.
+//!
+//! The text format is one `count name` line per card, as players write them:
+//!
+//! ```text
+//! # Burn
+//! 4 Lightning Bolt
+//! 2x Goblin Guide
+//!
+//! Sideboard
+//! 3 Smash to Smithereens
+//! ```
+//!
+//! Counts may end in `x`, lines starting with `#` or `//` are comments, and a `Sideboard` line
+//! (or an `SB:` prefix) puts cards in the sideboard. Card names are looked up in a `CardIndex`
+//! regardless of case.
+//!
+//! The JSON format is `{"cards": [{"id": 7, "name": "Lightning Bolt", "count": 4,
+//! "sideboard": false}]}`; an entry needs an `id` or a `name`. The deck code packs the ids and
+//! counts into URL-safe base64 for pasting into chat.
+//!
+//! Every problem found while importing is reported, each with the line of the text or the entry
+//! of the JSON or deck code it was found at.
+use serde_json::{self, Map, Value};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+/// The first byte of a deck code, bumped if the layout ever changes.
+const CODE_VERSION: u8 = 1;
+/// The URL-safe base64 alphabet.
+const BASE64: &'static [u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
+                                    abcdefghijklmnopqrstuvwxyz\
+                                    0123456789-_";
+
+/// The names of the cards that can be in a deck, by id.
+#[derive(Clone, Debug, Default)]
+pub struct CardIndex {
+    names: HashMap<u32, String>,
+    /// Ids by lower case name.
+    ids: HashMap<String, u32>,
+}
+
+impl CardIndex {
+    pub fn new() -> Self {
+        CardIndex::default()
+    }
+    pub fn insert(&mut self, id: u32, name: &str) {
+        self.names.insert(id, name.to_owned());
+        self.ids.insert(name.to_lowercase(), id);
+    }
+    pub fn name(&self, id: u32) -> Option<&str> {
+        self.names.get(&id).map(|n| n.as_str())
+    }
+    /// The id of the card called `name`, regardless of case.
+    pub fn id(&self, name: &str) -> Option<u32> {
+        self.ids.get(&name.to_lowercase()).cloned()
+    }
+}
+
+/// A card in a decklist.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeckEntry {
+    pub id: u32,
+    pub count: u32,
+    pub sideboard: bool,
+    pub name: String,
+}
+
+/// A decklist: the main deck and sideboard, in the order the cards were listed.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DeckList {
+    pub entries: Vec<DeckEntry>,
+}
+
+/// Where in an import a problem was found.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Location {
+    /// A 1-based line of a text decklist or of the JSON.
+    Line(usize),
+    /// A 1-based entry of the JSON `cards` or of a deck code.
+    Entry(usize),
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Location::Line(line) => write!(f, "line {}", line),
+            Location::Entry(entry) => write!(f, "entry {}", entry),
+        }
+    }
+}
+
+/// Something wrong with a decklist being imported.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DeckError {
+    /// A text line that isn't `count name`.
+    Syntax { line: usize, text: String },
+    /// A card name or id that isn't in the `CardIndex`.
+    UnknownCard { at: Location, card: String },
+    /// JSON that doesn't parse or an entry without the expected fields.
+    Json { at: Location, message: String },
+    /// An `id` or `count` that doesn't fit in a `u32`, a `count` of 0, or copies of a card
+    /// adding up to more than a `u32` holds, in which case `value` is their total.
+    OutOfRange {
+        at: Location,
+        what: &'static str,
+        value: u64,
+    },
+    /// A deck code that isn't valid base64 or ends early.
+    Code(String),
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DeckError::Syntax { line, ref text } => {
+                write!(f, "line {}: expected `count name`, found `{}`", line, text)
+            }
+            DeckError::UnknownCard { at, ref card } => {
+                write!(f, "{}: unknown card `{}`", at, card)
+            }
+            DeckError::Json { at, ref message } => write!(f, "{}: {}", at, message),
+            DeckError::OutOfRange { at, what, value } => {
+                write!(f, "{}: {} {} is out of range", at, what, value)
+            }
+            DeckError::Code(ref message) => write!(f, "invalid deck code: {}", message),
+        }
+    }
+}
+
+impl Error for DeckError {}
+
+impl DeckList {
+    pub fn new() -> Self {
+        DeckList::default()
+    }
+    /// Add `count` copies of a card, to the entry already listing it if there is one. The
+    /// count stops at `u32::max_value()`.
+    pub fn add(&mut self, id: u32, count: u32, sideboard: bool, name: &str) {
+        if self.add_checked(id, count, sideboard, name).is_err() {
+            if let Some(entry) = self.entries
+                   .iter_mut()
+                   .find(|e| e.id == id && e.sideboard == sideboard) {
+                entry.count = u32::max_value();
+            }
+        }
+    }
+    /// Like `add`, but leaves the deck as it is and returns the total if it would overflow.
+    fn add_checked(&mut self,
+                   id: u32,
+                   count: u32,
+                   sideboard: bool,
+                   name: &str)
+                   -> Result<(), u64> {
+        match self.entries.iter_mut().find(|e| e.id == id && e.sideboard == sideboard) {
+            Some(entry) => {
+                match entry.count.checked_add(count) {
+                    Some(total) => entry.count = total,
+                    None => return Err(entry.count as u64 + count as u64),
+                }
+            }
+            None => {
+                self.entries.push(DeckEntry {
+                                      id: id,
+                                      count: count,
+                                      sideboard: sideboard,
+                                      name: name.to_owned(),
+                                  })
+            }
+        }
+        Ok(())
+    }
+    /// Add copies of a card while importing, reporting a total that doesn't fit at `at`.
+    fn import(&mut self,
+              errors: &mut Vec<DeckError>,
+              at: Location,
+              id: u32,
+              count: u32,
+              sideboard: bool,
+              name: &str) {
+        if let Err(total) = self.add_checked(id, count, sideboard, name) {
+            errors.push(DeckError::OutOfRange {
+                            at: at,
+                            what: "count",
+                            value: total,
+                        });
+        }
+    }
+    pub fn main(&self) -> Vec<&DeckEntry> {
+        self.entries.iter().filter(|e| !e.sideboard).collect()
+    }
+    pub fn sideboard(&self) -> Vec<&DeckEntry> {
+        self.entries.iter().filter(|e| e.sideboard).collect()
+    }
+
+    /// The decklist as `count name` lines, with the sideboard after a `Sideboard` line.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        for entry in self.main() {
+            text.push_str(&format!("{} {}\n", entry.count, entry.name));
+        }
+        let sideboard = self.sideboard();
+        if !sideboard.is_empty() {
+            text.push_str("\nSideboard\n");
+            for entry in sideboard {
+                text.push_str(&format!("{} {}\n", entry.count, entry.name));
+            }
+        }
+        text
+    }
+    /// Read `count name` lines, looking the names up in `index`.
+    pub fn from_text(text: &str, index: &CardIndex) -> Result<DeckList, Vec<DeckError>> {
+        let mut deck = DeckList::new();
+        let mut errors = vec![];
+        let mut sideboard = false;
+        for (i, raw) in text.lines().enumerate() {
+            let line = raw.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+                continue;
+            }
+            let header = line.trim_end_matches(':').to_lowercase();
+            if header == "sideboard" || header == "deck" || header == "main" {
+                sideboard = header == "sideboard";
+                continue;
+            }
+            let prefixed = line.get(..3).map_or(false, |p| p.eq_ignore_ascii_case("sb:"));
+            let (in_sideboard, line) = if prefixed {
+                (true, line[3..].trim_start())
+            } else {
+                (sideboard, line)
+            };
+            let (count, name) = match parse_line(line) {
+                Some(parsed) => parsed,
+                None => {
+                    errors.push(DeckError::Syntax {
+                                    line: i + 1,
+                                    text: raw.to_owned(),
+                                });
+                    continue;
+                }
+            };
+            let at = Location::Line(i + 1);
+            match index.id(name) {
+                Some(id) => {
+                    let name = index.name(id).unwrap_or(name);
+                    deck.import(&mut errors, at, id, count, in_sideboard, name)
+                }
+                None => {
+                    errors.push(DeckError::UnknownCard {
+                                    at: at,
+                                    card: name.to_owned(),
+                                })
+                }
+            }
+        }
+        if errors.is_empty() { Ok(deck) } else { Err(errors) }
+    }
+
+    /// The decklist as pretty-printed JSON.
+    pub fn to_json(&self) -> String {
+        let cards = self.entries
+            .iter()
+            .map(|entry| {
+                let mut card = Map::new();
+                card.insert("id".to_owned(), Value::from(entry.id));
+                card.insert("name".to_owned(), Value::from(entry.name.clone()));
+                card.insert("count".to_owned(), Value::from(entry.count));
+                card.insert("sideboard".to_owned(), Value::from(entry.sideboard));
+                Value::Object(card)
+            })
+            .collect();
+        let mut root = Map::new();
+        root.insert("cards".to_owned(), Value::Array(cards));
+        // Serializing a `Value` can't fail.
+        serde_json::to_string_pretty(&Value::Object(root)).unwrap_or_default()
+    }
+    /// Read the JSON format. Entries are checked against `index` by id, or by name when they
+    /// have no id.
+    pub fn from_json(json: &str, index: &CardIndex) -> Result<DeckList, Vec<DeckError>> {
+        let root: Value = match serde_json::from_str(json) {
+            Ok(root) => root,
+            Err(e) => {
+                return Err(vec![DeckError::Json {
+                                    at: Location::Line(e.line()),
+                                    message: e.to_string(),
+                                }])
+            }
+        };
+        let cards = match root.get("cards").and_then(Value::as_array) {
+            Some(cards) => cards,
+            None => {
+                return Err(vec![DeckError::Json {
+                                    at: Location::Line(1),
+                                    message: "expected an object with a `cards` list".to_owned(),
+                                }])
+            }
+        };
+        let mut deck = DeckList::new();
+        let mut errors = vec![];
+        for (i, card) in cards.iter().enumerate() {
+            let at = Location::Entry(i + 1);
+            let count = card.get("count").and_then(Value::as_u64);
+            let sideboard = card.get("sideboard").and_then(Value::as_bool).unwrap_or(false);
+            let id = card.get("id").and_then(Value::as_u64);
+            let name = card.get("name").and_then(Value::as_str);
+            let count = match count {
+                Some(count) if count > 0 && count <= u32::max_value() as u64 => count as u32,
+                Some(count) => {
+                    errors.push(DeckError::OutOfRange {
+                                    at: at,
+                                    what: "count",
+                                    value: count,
+                                });
+                    continue;
+                }
+                None => {
+                    errors.push(DeckError::Json {
+                                    at: at,
+                                    message: "expected a positive `count`".to_owned(),
+                                });
+                    continue;
+                }
+            };
+            let found = match (id, name) {
+                (Some(id), _) if id > u32::max_value() as u64 => {
+                    errors.push(DeckError::OutOfRange {
+                                    at: at,
+                                    what: "id",
+                                    value: id,
+                                });
+                    continue;
+                }
+                (Some(id), _) => {
+                    let id = id as u32;
+                    index.name(id).map(|name| (id, name)).ok_or_else(|| id.to_string())
+                }
+                (None, Some(name)) => {
+                    index.id(name)
+                        .and_then(|id| index.name(id).map(|name| (id, name)))
+                        .ok_or_else(|| name.to_owned())
+                }
+                (None, None) => {
+                    errors.push(DeckError::Json {
+                                    at: at,
+                                    message: "expected an `id` or a `name`".to_owned(),
+                                });
+                    continue;
+                }
+            };
+            match found {
+                Ok((id, name)) => deck.import(&mut errors, at, id, count, sideboard, name),
+                Err(card) => {
+                    errors.push(DeckError::UnknownCard {
+                                    at: at,
+                                    card: card,
+                                })
+                }
+            }
+        }
+        if errors.is_empty() { Ok(deck) } else { Err(errors) }
+    }
+
+    /// The ids and counts packed into URL-safe base64. Names are left out and come back from
+    /// the `CardIndex`.
+    pub fn to_code(&self) -> String {
+        let mut bytes = vec![CODE_VERSION];
+        for part in [self.main(), self.sideboard()].iter() {
+            write_varint(&mut bytes, part.len() as u64);
+            for entry in part.iter() {
+                write_varint(&mut bytes, entry.id as u64);
+                write_varint(&mut bytes, entry.count as u64);
+            }
+        }
+        base64_encode(&bytes)
+    }
+    /// Read a deck code made by `to_code`.
+    pub fn from_code(code: &str, index: &CardIndex) -> Result<DeckList, Vec<DeckError>> {
+        let bytes = base64_decode(code.trim()).map_err(|e| vec![e])?;
+        match bytes.first() {
+            Some(&CODE_VERSION) => {}
+            Some(&v) => return Err(vec![DeckError::Code(format!("unknown version {}", v))]),
+            None => return Err(vec![DeckError::Code("empty".to_owned())]),
+        }
+        let mut pos = 1;
+        let mut deck = DeckList::new();
+        let mut errors = vec![];
+        let mut entry = 0;
+        for &sideboard in [false, true].iter() {
+            let len = read_varint(&bytes, &mut pos).map_err(|e| vec![e])?;
+            for _ in 0..len {
+                entry += 1;
+                let id = read_varint(&bytes, &mut pos).map_err(|e| vec![e])?;
+                let count = read_varint(&bytes, &mut pos).map_err(|e| vec![e])?;
+                let at = Location::Entry(entry);
+                if id > u32::max_value() as u64 {
+                    errors.push(DeckError::OutOfRange {
+                                    at: at,
+                                    what: "id",
+                                    value: id,
+                                });
+                    continue;
+                }
+                if count == 0 || count > u32::max_value() as u64 {
+                    errors.push(DeckError::OutOfRange {
+                                    at: at,
+                                    what: "count",
+                                    value: count,
+                                });
+                    continue;
+                }
+                let (id, count) = (id as u32, count as u32);
+                match index.name(id) {
+                    Some(name) => deck.import(&mut errors, at, id, count, sideboard, name),
+                    None => {
+                        errors.push(DeckError::UnknownCard {
+                                        at: at,
+                                        card: id.to_string(),
+                                    })
+                    }
+                }
+            }
+        }
+        if errors.is_empty() { Ok(deck) } else { Err(errors) }
+    }
+}
+
+/// `count name` or `countx name`.
+fn parse_line(line: &str) -> Option<(u32, &str)> {
+    let split = line.find(char::is_whitespace)?;
+    let (count, name) = line.split_at(split);
+    let count = count.trim_end_matches(|c| c == 'x' || c == 'X').parse::<u32>().ok()?;
+    let name = name.trim();
+    if count == 0 || name.is_empty() {
+        None
+    } else {
+        Some((count, name))
+    }
+}
+
+/// LEB128: seven bits per byte, low bits first, the top bit set on all but the last byte.
+fn write_varint(bytes: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        bytes.push((v as u8 & 0x7f) | 0x80);
+        v >>= 7;
+    }
+    bytes.push(v as u8);
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64, DeckError> {
+    let mut v = 0u64;
+    let mut shift = 0;
+    loop {
+        let b = match bytes.get(*pos) {
+            Some(&b) => b,
+            None => return Err(DeckError::Code("ends early".to_owned())),
+        };
+        *pos += 1;
+        if shift > 63 {
+            return Err(DeckError::Code("number too large".to_owned()));
+        }
+        v |= ((b & 0x7f) as u64) << shift;
+        if b & 0x80 == 0 {
+            return Ok(v);
+        }
+        shift += 7;
+    }
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity((bytes.len() * 4 + 2) / 3);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+        // Three bytes make four characters; a short chunk makes one character per six bits.
+        for i in 0..chunk.len() + 1 {
+            out.push(BASE64[(n >> (18 - 6 * i)) as usize & 0x3f] as char);
+        }
+    }
+    out
+}
+
+/// Decode URL-safe base64, with or without padding. The standard `+` and `/` are accepted too.
+fn base64_decode(code: &str) -> Result<Vec<u8>, DeckError> {
+    let code = code.trim_end_matches('=');
+    let mut bytes = Vec::with_capacity(code.len() * 3 / 4);
+    let (mut n, mut bits) = (0u32, 0);
+    for c in code.chars() {
+        let v = match c {
+            'A'..='Z' => c as u32 - 'A' as u32,
+            'a'..='z' => c as u32 - 'a' as u32 + 26,
+            '0'..='9' => c as u32 - '0' as u32 + 52,
+            '-' | '+' => 62,
+            '_' | '/' => 63,
+            _ => return Err(DeckError::Code(format!("unexpected `{}`", c))),
+        };
+        n = n << 6 | v;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((n >> bits) as u8);
+            n &= (1 << bits) - 1;
+        }
+    }
+    if bits >= 6 {
+        return Err(DeckError::Code("ends early".to_owned()));
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> CardIndex {
+        let mut index = CardIndex::new();
+        index.insert(7, "Lightning Bolt");
+        index.insert(8, "Goblin Guide");
+        index.insert(300, "Smash to Smithereens");
+        index
+    }
+
+    fn burn() -> DeckList {
+        let mut deck = DeckList::new();
+        deck.add(7, 4, false, "Lightning Bolt");
+        deck.add(8, 2, false, "Goblin Guide");
+        deck.add(300, 3, true, "Smash to Smithereens");
+        deck
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let deck = burn();
+        assert_eq!(DeckList::from_text(&deck.to_text(), &index()), Ok(deck));
+    }
+
+    #[test]
+    fn text_comments_counts_and_sideboard_prefix() {
+        let text = "# Burn\n4x lightning bolt\n// aggro\n2 Goblin Guide\n\
+                    SB: 3 Smash to Smithereens";
+        assert_eq!(DeckList::from_text(text, &index()), Ok(burn()));
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let deck = burn();
+        assert_eq!(DeckList::from_json(&deck.to_json(), &index()), Ok(deck));
+    }
+
+    #[test]
+    fn code_round_trip() {
+        let deck = burn();
+        let code = deck.to_code();
+        assert!(code.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_eq!(DeckList::from_code(&code, &index()), Ok(deck));
+    }
+
+    #[test]
+    fn text_errors_are_located_by_line() {
+        let text = "4 Lightning Bolt\nfour Goblin Guide\n\n1 Black Lotus";
+        assert_eq!(DeckList::from_text(text, &index()),
+                   Err(vec![DeckError::Syntax {
+                                line: 2,
+                                text: "four Goblin Guide".to_owned(),
+                            },
+                            DeckError::UnknownCard {
+                                at: Location::Line(4),
+                                card: "Black Lotus".to_owned(),
+                            }]));
+    }
+
+    #[test]
+    fn json_errors_are_located_by_entry() {
+        let json = r#"{"cards": [{"id": 7, "count": 4},
+                                 {"name": "Black Lotus", "count": 1},
+                                 {"id": 4294967303, "count": 1},
+                                 {"id": 8, "count": 0}]}"#;
+        let errors = DeckList::from_json(json, &index()).unwrap_err();
+        let at = errors.iter()
+            .map(|e| match *e {
+                     DeckError::UnknownCard { at, .. } |
+                     DeckError::Json { at, .. } |
+                     DeckError::OutOfRange { at, .. } => at,
+                     _ => panic!("unexpected {}", e),
+                 })
+            .collect::<Vec<_>>();
+        assert_eq!(at, vec![Location::Entry(2), Location::Entry(3), Location::Entry(4)]);
+        assert_eq!(errors[0],
+                   DeckError::UnknownCard {
+                       at: Location::Entry(2),
+                       card: "Black Lotus".to_owned(),
+                   });
+    }
+
+    #[test]
+    fn json_syntax_errors_give_the_line() {
+        match DeckList::from_json("{\"cards\": [\n{\"id\": }]}", &index()) {
+            Err(ref errors) if errors.len() == 1 => {
+                match errors[0] {
+                    DeckError::Json { at, .. } => assert_eq!(at, Location::Line(2)),
+                    ref e => panic!("unexpected {}", e),
+                }
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[test]
+    fn code_unknown_card_is_located_by_entry() {
+        let mut deck = burn();
+        deck.add(9, 1, true, "Black Lotus");
+        assert_eq!(DeckList::from_code(&deck.to_code(), &index()),
+                   Err(vec![DeckError::UnknownCard {
+                                at: Location::Entry(4),
+                                card: "9".to_owned(),
+                            }]));
+    }
+
+    #[test]
+    fn truncated_code_is_an_error() {
+        let code = burn().to_code();
+        match DeckList::from_code(&code[..code.len() - 2], &index()) {
+            Err(ref errors) => {
+                assert!(errors.iter().all(|e| match *e {
+                                              DeckError::Code(_) => true,
+                                              _ => false,
+                                          }))
+            }
+            Ok(deck) => panic!("unexpected {:?}", deck),
+        }
+    }
+
+    #[test]
+    fn bad_base64_is_an_error() {
+        assert_eq!(DeckList::from_code("AQI*", &index()),
+                   Err(vec![DeckError::Code("unexpected `*`".to_owned())]));
+    }
+
+    #[test]
+    fn code_rejects_zero_counts_and_large_ids() {
+        // Version 1, one main entry of card 7 with no copies, then one sideboard entry whose id
+        // doesn't fit in a u32.
+        let mut bytes = vec![CODE_VERSION, 1, 7, 0, 1];
+        write_varint(&mut bytes, 4294967303);
+        bytes.push(1);
+        assert_eq!(DeckList::from_code(&base64_encode(&bytes), &index()),
+                   Err(vec![DeckError::OutOfRange {
+                                at: Location::Entry(1),
+                                what: "count",
+                                value: 0,
+                            },
+                            DeckError::OutOfRange {
+                                at: Location::Entry(2),
+                                what: "id",
+                                value: 4294967303,
+                            }]));
+    }
+
+    #[test]
+    fn counts_adding_up_past_u32_are_out_of_range() {
+        let overflow = |at| {
+            vec![DeckError::OutOfRange {
+                     at: at,
+                     what: "count",
+                     value: 4294967296,
+                 }]
+        };
+        assert_eq!(DeckList::from_text("4294967295 Lightning Bolt\n1 Lightning Bolt", &index()),
+                   Err(overflow(Location::Line(2))));
+        let json = r#"{"cards": [{"id": 7, "count": 4294967295},
+                                 {"id": 7, "count": 1}]}"#;
+        assert_eq!(DeckList::from_json(json, &index()),
+                   Err(overflow(Location::Entry(2))));
+        // Two main entries of card 7, the first with as many copies as a u32 holds.
+        let mut bytes = vec![CODE_VERSION, 2, 7];
+        write_varint(&mut bytes, 4294967295);
+        bytes.extend_from_slice(&[7, 1, 0]);
+        assert_eq!(DeckList::from_code(&base64_encode(&bytes), &index()),
+                   Err(overflow(Location::Entry(2))));
+
+        let mut deck = DeckList::new();
+        deck.add(7, u32::max_value(), false, "Lightning Bolt");
+        deck.add(7, 1, false, "Lightning Bolt");
+        assert_eq!(deck.entries[0].count, u32::max_value());
+    }
+}
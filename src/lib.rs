@@ -1,6 +1,13 @@
 extern crate conrod_derive;
 #[macro_use]
 extern crate conrod_core;
+extern crate serde_json;
 pub mod custom_widget;
 pub mod sprite;
 pub mod text;
+pub mod atlas;
+pub mod locale;
+pub mod clock;
+pub mod tween;
+pub mod timeline;
+pub mod decklist;
@@ -0,0 +1,405 @@
+//! Message catalogs for translating widget labels.
+//!
+//! A catalog is a text file of `key = pattern` lines, one per message, named after its locale
+//! (`en.lang`, `fr.lang`, ...). Blank lines and lines starting with `#` are ignored, and `\n` in a
+//! pattern is a line break.
+//!
+//! ```text
+//! # en.lang
+//! table.ready = Ready
+//! table.players[one] = {count} player
+//! table.players[other] = {count} players
+//! ```
+//!
+//! `{name}` in a pattern is replaced by the argument called `name`; placeholders without an
+//! argument and `{{` escapes are left alone, so `rich_text` markup passes through. A key with
+//! `[one]`, `[few]`, ... suffixes is a plural message, picked with `Locale::format_plural` using
+//! the rules of the catalog's language.
+use custom_widget::table_list::TableListTexts;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::Path;
+
+/// The file extension `Locale::load_dir` looks for.
+pub const CATALOG_EXTENSION: &'static str = "lang";
+
+/// Everything that can go wrong while loading a catalog.
+#[derive(Debug)]
+pub enum LocaleError {
+    Io(io::Error),
+    /// A line that isn't `key = pattern`, with its 1-based line number.
+    Syntax { line: usize, text: String },
+    /// A file whose name doesn't give a locale.
+    NoLocale(String),
+}
+
+impl fmt::Display for LocaleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LocaleError::Io(ref e) => write!(f, "could not read catalog: {}", e),
+            LocaleError::Syntax { line, ref text } => {
+                write!(f, "line {}: expected `key = pattern`, found `{}`", line, text)
+            }
+            LocaleError::NoLocale(ref path) => write!(f, "no locale in file name `{}`", path),
+        }
+    }
+}
+
+impl Error for LocaleError {}
+
+impl From<io::Error> for LocaleError {
+    fn from(e: io::Error) -> Self {
+        LocaleError::Io(e)
+    }
+}
+
+/// CLDR plural categories.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+impl PluralCategory {
+    /// The suffix used for this category in catalog keys.
+    pub fn as_str(&self) -> &'static str {
+        match *self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+}
+
+/// The plural category of `n` in `locale`, e.g. "en", "fr_FR" or "pt-BR".
+///
+/// Covers the common rule families; unknown languages use the English rule.
+pub fn plural_category(locale: &str, n: u64) -> PluralCategory {
+    use self::PluralCategory::*;
+    let lang = locale.split(|c| c == '_' || c == '-').next().unwrap_or("");
+    let (n10, n100) = (n % 10, n % 100);
+    let slavic_few = n10 >= 2 && n10 <= 4 && !(n100 >= 12 && n100 <= 14);
+    match lang {
+        "ja" | "zh" | "ko" | "th" | "vi" | "id" | "ms" | "tr" => Other,
+        "fr" | "pt" => if n <= 1 { One } else { Other },
+        "ru" | "uk" | "be" => {
+            if n10 == 1 && n100 != 11 {
+                One
+            } else if slavic_few {
+                Few
+            } else {
+                Many
+            }
+        }
+        "pl" => {
+            if n == 1 {
+                One
+            } else if slavic_few {
+                Few
+            } else {
+                Many
+            }
+        }
+        "cs" | "sk" => {
+            match n {
+                1 => One,
+                2..=4 => Few,
+                _ => Other,
+            }
+        }
+        "ar" => {
+            match (n, n100) {
+                (0, _) => Zero,
+                (1, _) => One,
+                (2, _) => Two,
+                (_, 3..=10) => Few,
+                (_, 11..=99) => Many,
+                _ => Other,
+            }
+        }
+        _ => if n == 1 { One } else { Other },
+    }
+}
+
+/// The messages of one locale.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Catalog {
+    pub locale: String,
+    messages: HashMap<String, String>,
+}
+impl Catalog {
+    pub fn new(locale: &str) -> Self {
+        Catalog {
+            locale: locale.to_owned(),
+            messages: HashMap::new(),
+        }
+    }
+    /// Parse catalog text for `locale`.
+    pub fn parse(locale: &str, text: &str) -> Result<Catalog, LocaleError> {
+        let mut catalog = Catalog::new(locale);
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let eq = match line.find('=') {
+                Some(eq) if !line[..eq].trim().is_empty() => eq,
+                _ => {
+                    return Err(LocaleError::Syntax {
+                                   line: i + 1,
+                                   text: line.to_owned(),
+                               })
+                }
+            };
+            let pattern = line[eq + 1..].trim().replace("\\n", "\n");
+            catalog.insert(line[..eq].trim(), &pattern);
+        }
+        Ok(catalog)
+    }
+    /// Load a catalog file. The locale is the file name without its extension.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Catalog, LocaleError> {
+        let path = path.as_ref();
+        let locale = match path.file_stem().and_then(|s| s.to_str()) {
+            Some(locale) => locale.to_owned(),
+            None => return Err(LocaleError::NoLocale(path.display().to_string())),
+        };
+        let mut text = String::new();
+        File::open(path)?.read_to_string(&mut text)?;
+        Catalog::parse(&locale, &text)
+    }
+    /// Add or replace a message. Plural forms use keys such as `"cards[one]"`.
+    pub fn insert(&mut self, key: &str, pattern: &str) {
+        self.messages.insert(key.to_owned(), pattern.to_owned());
+    }
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.messages.get(key).map(|s| s.as_str())
+    }
+    /// The pattern for `key` when counting `n`, falling back to `key[other]` and then `key`.
+    pub fn get_plural(&self, key: &str, n: u64) -> Option<&str> {
+        let category = plural_category(&self.locale, n);
+        self.get(&format!("{}[{}]", key, category.as_str()))
+            .or_else(|| self.get(&format!("{}[other]", key)))
+            .or_else(|| self.get(key))
+    }
+}
+
+/// A set of catalogs, one of which is current.
+///
+/// Widgets look messages up every time they are set, so calling `set_locale` switches the whole
+/// UI on the next frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Locale {
+    catalogs: HashMap<String, Catalog>,
+    current: String,
+    fallback: Option<String>,
+}
+impl Locale {
+    /// An empty set of catalogs with `current` as the current locale.
+    pub fn new(current: &str) -> Self {
+        Locale {
+            catalogs: HashMap::new(),
+            current: current.to_owned(),
+            fallback: None,
+        }
+    }
+    /// Load every `*.lang` file in `dir`.
+    pub fn load_dir<P: AsRef<Path>>(mut self, dir: P) -> Result<Self, LocaleError> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) == Some(CATALOG_EXTENSION) {
+                self.add(Catalog::load(&path)?);
+            }
+        }
+        Ok(self)
+    }
+    /// Add a catalog, replacing any with the same locale.
+    pub fn add(&mut self, catalog: Catalog) {
+        self.catalogs.insert(catalog.locale.clone(), catalog);
+    }
+    /// Use messages from `locale` when the current catalog is missing them.
+    pub fn fallback(mut self, locale: &str) -> Self {
+        self.fallback = Some(locale.to_owned());
+        self
+    }
+    /// Switch to `locale`. Returns `false`, leaving the locale unchanged, if it has no catalog.
+    pub fn set_locale(&mut self, locale: &str) -> bool {
+        if self.catalogs.contains_key(locale) {
+            self.current = locale.to_owned();
+            true
+        } else {
+            false
+        }
+    }
+    pub fn current(&self) -> &str {
+        &self.current
+    }
+    /// The locales that have a catalog, in no particular order.
+    pub fn locales(&self) -> Vec<&str> {
+        self.catalogs.keys().map(|k| k.as_str()).collect()
+    }
+    /// The message for `key`, or `key` itself when no catalog has it.
+    pub fn tr<'b>(&'b self, key: &'b str) -> &'b str {
+        self.lookup(|c| c.get(key)).unwrap_or(key)
+    }
+    /// The message for `key` with `{name}` placeholders replaced from `args`.
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        fill(self.tr(key), args)
+    }
+    /// The plural form of `key` for `n`. `{count}` is replaced by `n` as well as any `args`.
+    pub fn format_plural(&self, key: &str, n: u64, args: &[(&str, &str)]) -> String {
+        let pattern = self.lookup(|c| c.get_plural(key, n)).unwrap_or(key);
+        let count = n.to_string();
+        let mut all = vec![("count", count.as_str())];
+        all.extend_from_slice(args);
+        fill(pattern, &all)
+    }
+    fn lookup<'b, F>(&'b self, f: F) -> Option<&'b str>
+        where F: Fn(&'b Catalog) -> Option<&'b str>
+    {
+        self.catalogs
+            .get(&self.current)
+            .and_then(|c| f(c))
+            .or_else(|| {
+                         self.fallback
+                             .as_ref()
+                             .and_then(|l| self.catalogs.get(l))
+                             .and_then(|c| f(c))
+                     })
+    }
+}
+
+/// `key` translated by `locale`, or `key` as it is for widgets that weren't given a locale.
+pub fn tr_or<'b>(locale: Option<&'b Locale>, key: &'b str) -> &'b str {
+    match locale {
+        Some(locale) => locale.tr(key),
+        None => key,
+    }
+}
+
+/// Replace `{name}` in `pattern` with the matching argument. Unknown placeholders and `{{` are
+/// kept as written, so the result is still `RichText` markup, where `{{` is a literal `{`.
+pub fn fill(pattern: &str, args: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(pattern.len());
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if rest.starts_with("{{") {
+            out.push_str("{{");
+            rest = &rest[2..];
+            continue;
+        }
+        let end = match rest.find('}') {
+            Some(end) => end,
+            None => break,
+        };
+        let name = &rest[1..end];
+        match args.iter().find(|&&(k, _)| k == name) {
+            Some(&(_, v)) => out.push_str(v),
+            None => out.push_str(&rest[..end + 1]),
+        }
+        rest = &rest[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Catalog keys used when a `Locale` is handed to `TableList`.
+impl TableListTexts for Locale {
+    fn text_ready(&self) -> &str {
+        self.tr("table_list.ready")
+    }
+    fn text_leave(&self) -> &str {
+        self.tr("table_list.leave")
+    }
+    fn text_join(&self) -> &str {
+        self.tr("table_list.join")
+    }
+    fn text_playergame(&self) -> &str {
+        self.tr("table_list.playergame")
+    }
+    fn text_changeto(&self) -> &str {
+        self.tr("table_list.changeto")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn locale() -> Locale {
+        let en = Catalog::parse("en",
+                                "# English\nready = Ready\n\
+                                 cards[one] = {count} card\ncards[other] = {count} cards\n\
+                                 hand = {who} holds {count} {{cards}\\n{gold}")
+            .unwrap();
+        let ru = Catalog::parse("ru",
+                                "cards[one] = {count} карта\ncards[few] = {count} карты\n\
+                                 cards[many] = {count} карт")
+            .unwrap();
+        let mut locale = Locale::new("en").fallback("en");
+        locale.add(en);
+        locale.add(ru);
+        locale
+    }
+
+    #[test]
+    fn plural_rules() {
+        use super::PluralCategory::*;
+        let en = [0, 1, 2, 11].iter().map(|&n| plural_category("en", n)).collect::<Vec<_>>();
+        assert_eq!(en, vec![Other, One, Other, Other]);
+        let fr = [0, 1, 2].iter().map(|&n| plural_category("fr_FR", n)).collect::<Vec<_>>();
+        assert_eq!(fr, vec![One, One, Other]);
+        let ru = [1, 2, 5, 11, 12, 21, 22, 25, 111]
+            .iter()
+            .map(|&n| plural_category("ru-RU", n))
+            .collect::<Vec<_>>();
+        assert_eq!(ru, vec![One, Few, Many, Many, Many, One, Few, Many, Many]);
+        let ar = [0, 1, 2, 3, 11, 100]
+            .iter()
+            .map(|&n| plural_category("ar", n))
+            .collect::<Vec<_>>();
+        assert_eq!(ar, vec![Zero, One, Two, Few, Many, Other]);
+        assert_eq!(plural_category("ja", 1), Other);
+        assert_eq!(plural_category("xx", 1), One);
+    }
+
+    #[test]
+    fn fill_keeps_unknown_placeholders_and_escapes() {
+        assert_eq!(fill("{who} pays {gold} {{x} {who", &[("who", "Ann")]),
+                   "Ann pays {gold} {{x} {who");
+        assert_eq!(fill("no placeholders", &[("who", "Ann")]), "no placeholders");
+    }
+
+    #[test]
+    fn lookups_fall_back_to_the_key() {
+        let mut locale = locale();
+        assert_eq!(locale.tr("ready"), "Ready");
+        assert_eq!(locale.tr("missing"), "missing");
+        assert!(!locale.set_locale("de"));
+        assert!(locale.set_locale("ru"));
+        assert_eq!(locale.tr("ready"), "Ready");
+    }
+
+    #[test]
+    fn format_plural_picks_the_category_and_fills_the_count() {
+        let mut locale = locale();
+        assert_eq!(locale.format_plural("cards", 1, &[]), "1 card");
+        assert_eq!(locale.format_plural("cards", 3, &[]), "3 cards");
+        assert_eq!(locale.format("hand", &[("who", "Ann"), ("count", "2")]),
+                   "Ann holds 2 {{cards}\n{gold}");
+        locale.set_locale("ru");
+        assert_eq!(locale.format_plural("cards", 3, &[]), "3 карты");
+        assert_eq!(locale.format_plural("cards", 5, &[]), "5 карт");
+        assert_eq!(locale.format_plural("cards", 21, &[]), "21 карта");
+    }
+}
@@ -1,3 +1,5 @@
+use std::ops::Range;
+use std::time::Duration;
 #[derive(Clone,Copy,PartialEq,Debug)]
 pub struct SpriteInfo {
     pub first: (f64, f64), //left corner of first
@@ -29,10 +31,132 @@ pub trait Spriteable {
     fn num_in_col(&self) -> u16;
     fn w_h(&self) -> (f64, f64);
     fn pad(&self) -> (f64, f64, f64, f64);
+    /// The number of frames to step through. A full grid by default.
+    fn num_frames(&self) -> u16 {
+        self.num_in_row() * self.num_in_col()
+    }
+    /// The corners of frame `index`. Sprites that are not laid out on a uniform grid override this.
+    fn frame_rect(&self, index: f64) -> ([f64; 2], [f64; 2]) {
+        grid_rect(self, index)
+    }
+    /// How long frame `index` should be shown for, if the sprite knows.
+    fn frame_duration(&self, _index: usize) -> Option<Duration> {
+        None
+    }
 }
 pub fn spriteable_rect<H: Spriteable>(s: H, index: f64) -> ([f64; 2], [f64; 2]) {
+    s.frame_rect(index)
+}
+fn grid_rect<H: Spriteable + ?Sized>(s: &H, index: f64) -> ([f64; 2], [f64; 2]) {
     let (x, y) = (index % s.num_in_row() as f64, (index / (s.num_in_row() as f64)).floor());
     ([s.first().0 + x * s.w_h().0 + s.pad().0, s.first().1 - y * s.w_h().1 - s.pad().2],
      [s.first().0 + (x + 1.0) * s.w_h().0 - s.pad().1,
       s.first().1 - (y + 1.0) * s.w_h().1 + s.pad().3])
 }
+
+/// A named range of frames inside a `FrameList`, e.g. "idle" or "attack".
+#[derive(Clone, Debug, PartialEq)]
+pub struct Clip {
+    pub name: String,
+    pub frames: Range<usize>,
+}
+
+/// A sprite made of an explicit list of source rects, each of which may have its own size.
+///
+/// Unlike `SpriteInfo` the frames don't need to sit on a grid. Frames can be grouped into named
+/// clips, and `clip_sprite` gives a `FrameList` that plays a single clip.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameList {
+    /// Corners of every frame, in the same bottom-left origin as `spriteable_rect`.
+    pub frames: Vec<([f64; 2], [f64; 2])>,
+    pub clips: Vec<Clip>,
+    /// Per-frame display times. Missing entries fall back to the player's default.
+    pub durations: Vec<Option<Duration>>,
+}
+impl FrameList {
+    pub fn new() -> Self {
+        FrameList::default()
+    }
+    /// Build from `[x, y, w, h]` pixel rects measured from the top left of an image `image_h`
+    /// pixels high, which is how image editors and atlas exporters describe them.
+    pub fn from_pixel_rects(image_h: f64, rects: &[[f64; 4]]) -> Self {
+        let frames = rects.iter()
+            .map(|&[x, y, w, h]| ([x, image_h - y], [x + w, image_h - y - h]))
+            .collect();
+        FrameList {
+            frames: frames,
+            clips: vec![],
+            durations: vec![],
+        }
+    }
+    /// Append a frame given by its corners.
+    pub fn frame(mut self, corners: ([f64; 2], [f64; 2])) -> Self {
+        self.frames.push(corners);
+        self
+    }
+    /// Set how long each frame is shown, in frame order.
+    pub fn durations(mut self, durations: Vec<Duration>) -> Self {
+        self.durations = durations.into_iter().map(Some).collect();
+        self
+    }
+    /// Name the frames in `frames`.
+    pub fn clip(mut self, name: &str, frames: Range<usize>) -> Self {
+        self.clips.push(Clip {
+                            name: name.to_owned(),
+                            frames: frames,
+                        });
+        self
+    }
+    /// The frame range of the clip called `name`.
+    pub fn clip_range(&self, name: &str) -> Option<Range<usize>> {
+        self.clips.iter().find(|c| c.name == name).map(|c| c.frames.clone())
+    }
+    /// A `FrameList` holding only the frames of the clip called `name`, so it can be handed to
+    /// `FullCycleSprite` or any other `Spriteable` user.
+    pub fn clip_sprite(&self, name: &str) -> Option<FrameList> {
+        self.clip_range(name).and_then(|r| {
+            self.frames.get(r.clone()).map(|frames| {
+                FrameList {
+                    frames: frames.to_vec(),
+                    clips: vec![],
+                    durations: r.map(|i| self.durations.get(i).and_then(|d| *d)).collect(),
+                }
+            })
+        })
+    }
+    fn frame_at(&self, index: f64) -> ([f64; 2], [f64; 2]) {
+        if self.frames.is_empty() {
+            return ([0.0, 0.0], [0.0, 0.0]);
+        }
+        self.frames[index.max(0.0) as usize % self.frames.len()]
+    }
+}
+impl Spriteable for FrameList {
+    fn first(&self) -> (f64, f64) {
+        let (a, _) = self.frame_at(0.0);
+        (a[0], a[1])
+    }
+    fn num_in_row(&self) -> u16 {
+        self.frames.len() as u16
+    }
+    fn num_in_col(&self) -> u16 {
+        1
+    }
+    /// The size of the first frame; the other frames may differ.
+    fn w_h(&self) -> (f64, f64) {
+        let (a, b) = self.frame_at(0.0);
+        ((b[0] - a[0]).abs(), (b[1] - a[1]).abs())
+    }
+    fn pad(&self) -> (f64, f64, f64, f64) {
+        (0.0, 0.0, 0.0, 0.0)
+    }
+    fn num_frames(&self) -> u16 {
+        self.frames.len() as u16
+    }
+    fn frame_rect(&self, index: f64) -> ([f64; 2], [f64; 2]) {
+        self.frame_at(index.floor())
+    }
+    fn frame_duration(&self, index: usize) -> Option<Duration> {
+        self.durations.get(index).and_then(|d| *d)
+    }
+}
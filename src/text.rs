@@ -1,4 +1,5 @@
-use conrod_core::{Scalar, FontSize};
+use conrod_core::{Scalar, FontSize, Ui};
+use conrod_core::text::{self, line, font, Font};
 use std::cmp::min;
 const LABEL_PADDING: f64 = 4.0;
 /// Return the dimensions of a value glyph slot.
@@ -6,11 +7,15 @@ pub fn value_glyph_slot_width(size: FontSize) -> f64 {
     (size as f64 * 0.75).floor() as f64
 }
 /// Return the dimensions of value string glyphs.
+///
+/// Assumes every glyph is `0.75 * font_size` wide. Prefer `text_width` when a font is loaded.
 pub fn calc_width(font_size: FontSize, val_string: &String) -> f64 {
     let slot_w = value_glyph_slot_width(font_size);
     let val_string_w = slot_w * val_string.len() as f64;
     val_string_w
 }
+/// Estimate the font size that fits `val_string` in `w` by `h`. Prefer `fit_font_size` when a
+/// font is loaded.
 pub fn get_font_size_wh(w: Scalar, h: Scalar, val_string: &str) -> FontSize {
     min((w / (val_string.len() as f64) / 0.75).floor() as u32,
         (h - 2.0 * LABEL_PADDING).floor() as u32)
@@ -22,3 +27,56 @@ pub fn get_font_size_hn(h: Scalar, numberline: Scalar) -> FontSize {
 pub fn calc_height(font_size: FontSize) -> Scalar {
     font_size as Scalar + LABEL_PADDING * 2.0
 }
+
+/// The font a `widget::Text` would use for `font_id`: the given one, else the theme's, else the
+/// first loaded font.
+pub fn font_of(ui: &Ui, font_id: Option<font::Id>) -> Option<&Font> {
+    font_id.or(ui.theme.font_id)
+        .or(ui.fonts.ids().next())
+        .and_then(|id| ui.fonts.get(id))
+}
+/// The width of `val` on a single line, from the font's glyph advances.
+pub fn text_width(font: &Font, font_size: FontSize, val: &str) -> Scalar {
+    line::width(val, font, font_size)
+}
+/// The `[w, h]` of `val` once wrapped at whitespace to fit `max_w`.
+///
+/// `w` is the widest resulting line, which can exceed `max_w` when a single word is too long.
+pub fn wrapped_size(font: &Font,
+                    font_size: FontSize,
+                    val: &str,
+                    max_w: Scalar,
+                    line_spacing: Scalar)
+                    -> [Scalar; 2] {
+    let (num_lines, w) = line::infos(val, font, font_size)
+        .wrap_by_whitespace(max_w)
+        .fold((0, 0.0f64), |(n, w), info| (n + 1, w.max(info.width)));
+    [w, text::height(num_lines, font_size, line_spacing)]
+}
+/// The largest font size at which `val` fits within `w` by `h` on a single line.
+pub fn fit_font_size(font: &Font, val: &str, w: Scalar, h: Scalar) -> FontSize {
+    largest_fitting(h, |size| text_width(font, size, val) <= w)
+}
+/// The largest font size at which `val`, wrapped at whitespace, fits within `w` by `h`.
+pub fn fit_font_size_wrapped(font: &Font,
+                             val: &str,
+                             w: Scalar,
+                             h: Scalar,
+                             line_spacing: Scalar)
+                             -> FontSize {
+    largest_fitting(h, |size| {
+        let [lw, lh] = wrapped_size(font, size, val, w, line_spacing);
+        lw <= w && lh <= h
+    })
+}
+/// Binary search for the largest size in `1..=max_h` that `fits`, or 1 if none do.
+fn largest_fitting<F>(max_h: Scalar, fits: F) -> FontSize
+    where F: Fn(FontSize) -> bool
+{
+    let (mut lo, mut hi) = (1, max_h.max(1.0).floor() as FontSize);
+    while lo < hi {
+        let mid = (lo + hi + 1) / 2;
+        if fits(mid) { lo = mid } else { hi = mid - 1 }
+    }
+    lo
+}
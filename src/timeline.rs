@@ -0,0 +1,270 @@
+//! Chaining widget animations one after another and side by side.
+//!
+//! A `Timeline` is built from `Step`s: named animations, delays and callbacks, grouped into
+//! sequences and parallel groups. Each update, call `Timeline::update`, set the widgets whose
+//! step `is_active` and pass their events to `Timeline::report`. A step finishes when its
+//! widget's event says the animation is over, which every animated widget event shares through
+//! `AnimationEvent`.
+//!
+//! ```ignore
+//! let mut turn = Timeline::new()
+//!     .then(Step::animation("shuffle"))
+//!     .then(Step::par(vec![Step::animation("deal"), Step::animation("flip")]))
+//!     .then(Step::call(Box::new(|| println!("dealt"))));
+//! // every update:
+//! turn.update(&SYSTEM_CLOCK);
+//! if turn.is_active("shuffle") {
+//!     let e = Shuffle::new(&cards, widget_closure, back).set(ids.shuffle, ui);
+//!     turn.report("shuffle", &e);
+//! }
+//! ```
+use clock::Clock;
+use custom_widget::animated_canvas::CanvasY;
+use custom_widget::card_flip::Flip;
+use custom_widget::flying_card::Flights;
+use custom_widget::instructionset::InstructionsOpen;
+use custom_widget::full_cycle_sprite::Playback;
+use custom_widget::shuffle::ShuffleEvent;
+use std::time::{Duration, Instant};
+
+/// The event of a widget that plays an animation to an end.
+pub trait AnimationEvent {
+    /// `true` once the animation is over.
+    fn is_finished(&self) -> bool;
+}
+impl AnimationEvent for InstructionsOpen {
+    fn is_finished(&self) -> bool {
+        !self.0
+    }
+}
+impl AnimationEvent for ShuffleEvent {
+    fn is_finished(&self) -> bool {
+        ShuffleEvent::is_finished(self)
+    }
+}
+impl AnimationEvent for CanvasY {
+    fn is_finished(&self) -> bool {
+        self.is_done()
+    }
+}
+impl AnimationEvent for Playback {
+    fn is_finished(&self) -> bool {
+        Playback::is_finished(self)
+    }
+}
+impl AnimationEvent for Flip {
+    fn is_finished(&self) -> bool {
+        Flip::is_finished(self)
+    }
+}
+impl<T> AnimationEvent for Flights<T> {
+    fn is_finished(&self) -> bool {
+        Flights::is_finished(self)
+    }
+}
+
+/// A part of a `Timeline`.
+pub struct Step(Node);
+
+enum Node {
+    Animation { name: String, done: bool },
+    Delay {
+        duration: Duration,
+        start: Option<Instant>,
+        done: bool,
+    },
+    Call(Option<Box<FnMut()>>),
+    Seq(Vec<Node>),
+    Par(Vec<Node>),
+}
+
+impl Step {
+    /// An animation played by a widget. It finishes when an event reported under `name` says
+    /// so, or when `Timeline::finish` is called with `name`.
+    pub fn animation<S: Into<String>>(name: S) -> Self {
+        Step(Node::Animation {
+                 name: name.into(),
+                 done: false,
+             })
+    }
+    /// Nothing happens for `duration`.
+    pub fn delay(duration: Duration) -> Self {
+        Step(Node::Delay {
+                 duration: duration,
+                 start: None,
+                 done: false,
+             })
+    }
+    /// Calls `f` once, when the steps before it have finished.
+    pub fn call(f: Box<FnMut()>) -> Self {
+        Step(Node::Call(Some(f)))
+    }
+    /// `steps` one after another.
+    pub fn seq(steps: Vec<Step>) -> Self {
+        Step(Node::Seq(steps.into_iter().map(|s| s.0).collect()))
+    }
+    /// `steps` all at once, finishing when the last of them does.
+    pub fn par(steps: Vec<Step>) -> Self {
+        Step(Node::Par(steps.into_iter().map(|s| s.0).collect()))
+    }
+}
+
+impl Node {
+    /// Start or move on whatever can go ahead at `now`. Returns `true` once finished.
+    fn poll(&mut self, now: Instant) -> bool {
+        match *self {
+            Node::Animation { done, .. } => done,
+            Node::Delay { duration, ref mut start, ref mut done } => {
+                let start = *start.get_or_insert(now);
+                *done = *done || now >= start + duration;
+                *done
+            }
+            Node::Call(ref mut f) => {
+                if let Some(mut f) = f.take() {
+                    f();
+                }
+                true
+            }
+            Node::Seq(ref mut nodes) => nodes.iter_mut().all(|n| n.poll(now)),
+            Node::Par(ref mut nodes) => {
+                nodes.iter_mut().fold(true, |done, n| n.poll(now) && done)
+            }
+        }
+    }
+    fn is_finished(&self) -> bool {
+        match *self {
+            Node::Animation { done, .. } |
+            Node::Delay { done, .. } => done,
+            Node::Call(ref f) => f.is_none(),
+            Node::Seq(ref nodes) |
+            Node::Par(ref nodes) => nodes.iter().all(Node::is_finished),
+        }
+    }
+    /// `true` if an animation named `name` is playing: not finished, with everything before it
+    /// in its sequences finished.
+    fn is_active(&self, name: &str) -> bool {
+        match *self {
+            Node::Animation { name: ref n, done } => !done && n == name,
+            Node::Seq(ref nodes) => {
+                nodes.iter().find(|n| !n.is_finished()).map_or(false, |n| n.is_active(name))
+            }
+            Node::Par(ref nodes) => nodes.iter().any(|n| n.is_active(name)),
+            _ => false,
+        }
+    }
+    /// Finish the playing animations named `name`.
+    fn finish(&mut self, name: &str) {
+        match *self {
+            Node::Animation { name: ref n, ref mut done } => {
+                if n == name {
+                    *done = true;
+                }
+            }
+            Node::Seq(ref mut nodes) => {
+                if let Some(node) = nodes.iter_mut().find(|n| !n.is_finished()) {
+                    node.finish(name);
+                }
+            }
+            Node::Par(ref mut nodes) => {
+                for node in nodes.iter_mut() {
+                    node.finish(name);
+                }
+            }
+            _ => {}
+        }
+    }
+    /// Finish everything, calling the callbacks that haven't been called in order.
+    fn skip(&mut self) {
+        match *self {
+            Node::Animation { ref mut done, .. } |
+            Node::Delay { ref mut done, .. } => *done = true,
+            Node::Call(ref mut f) => {
+                if let Some(mut f) = f.take() {
+                    f();
+                }
+            }
+            Node::Seq(ref mut nodes) |
+            Node::Par(ref mut nodes) => {
+                for node in nodes.iter_mut() {
+                    node.skip();
+                }
+            }
+        }
+    }
+}
+
+/// A sequence of steps run as one animation.
+pub struct Timeline {
+    root: Node,
+    finished: bool,
+    skipped: bool,
+}
+
+impl Timeline {
+    /// An empty timeline, which finishes on its first update.
+    pub fn new() -> Self {
+        Timeline {
+            root: Node::Seq(vec![]),
+            finished: false,
+            skipped: false,
+        }
+    }
+    /// Play `step` after the steps added so far.
+    pub fn then(mut self, step: Step) -> Self {
+        if let Node::Seq(ref mut nodes) = self.root {
+            nodes.push(step.0);
+        }
+        self
+    }
+    /// Wait for `duration` after the steps added so far.
+    pub fn wait(self, duration: Duration) -> Self {
+        self.then(Step::delay(duration))
+    }
+    /// Move the timeline on: start delays, run callbacks and begin the steps whose turn has
+    /// come. Call once per update, before setting the widgets. Returns `true` once finished.
+    pub fn update(&mut self, clock: &Clock) -> bool {
+        if !self.finished {
+            self.finished = self.root.poll(clock.now());
+        }
+        self.finished
+    }
+    /// `true` while the animation `name` should be playing, i.e. its widget should be set.
+    pub fn is_active(&self, name: &str) -> bool {
+        !self.finished && self.root.is_active(name)
+    }
+    /// Finish the playing animation `name` if `event` says it is over. The next `update`
+    /// moves on to the steps after it.
+    pub fn report<E: AnimationEvent>(&mut self, name: &str, event: &E) {
+        if event.is_finished() {
+            self.finish(name);
+        }
+    }
+    /// Finish the playing animation `name`, e.g. for widgets without an `AnimationEvent`.
+    pub fn finish(&mut self, name: &str) {
+        self.root.finish(name);
+    }
+    /// Jump to the end. Callbacks that haven't run yet are run now in order, so the game state
+    /// ends up where the animation would have left it. Widgets are left to show their final
+    /// state by the caller, e.g. when `was_skipped`.
+    pub fn skip(&mut self) {
+        if !self.finished {
+            self.root.skip();
+            self.finished = true;
+            self.skipped = true;
+        }
+    }
+    /// `true` once every step has finished or the timeline was skipped.
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    /// `true` if the timeline ended through `skip`.
+    pub fn was_skipped(&self) -> bool {
+        self.skipped
+    }
+}
+
+impl Default for Timeline {
+    fn default() -> Self {
+        Timeline::new()
+    }
+}
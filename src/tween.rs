@@ -0,0 +1,351 @@
+//! Easing curves and tweens for widget transitions.
+//!
+//! A `Tween` moves a value from `from` to `to` over a `Duration`, after an optional delay, along
+//! an `Easing` curve. A `Sequence` plays tweens one after another. Both are sampled with the time
+//! elapsed since they started, which pairs with `clock::since`.
+use conrod_core::Color;
+use clock::secs;
+use std::f64::consts::PI;
+use std::time::Duration;
+
+/// The standard easing curves. `In` curves start slowly, `Out` curves end slowly and `InOut`
+/// curves do both.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    /// Pulls back a little before moving.
+    BackIn,
+    /// Overshoots a little before settling.
+    BackOut,
+    BackInOut,
+    ElasticIn,
+    /// Springs past the end and wobbles back.
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    /// Bounces against the end like a dropped ball.
+    BounceOut,
+    BounceInOut,
+}
+impl Default for Easing {
+    fn default() -> Self {
+        Easing::Linear
+    }
+}
+impl Easing {
+    /// Map progress `t`, clamped to `0.0..1.0`, onto the curve. The result is 0 at the start and 1
+    /// at the end, but `Back` and `Elastic` curves leave that range in between.
+    pub fn apply(&self, t: f64) -> f64 {
+        let t = t.max(0.0).min(1.0);
+        const C1: f64 = 1.70158;
+        const C2: f64 = C1 * 1.525;
+        const C3: f64 = C1 + 1.0;
+        match *self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::BackIn => C3 * t * t * t - C1 * t * t,
+            Easing::BackOut => 1.0 + C3 * (t - 1.0).powi(3) + C1 * (t - 1.0).powi(2),
+            Easing::BackInOut => {
+                if t < 0.5 {
+                    (2.0 * t).powi(2) * ((C2 + 1.0) * 2.0 * t - C2) / 2.0
+                } else {
+                    ((2.0 * t - 2.0).powi(2) * ((C2 + 1.0) * (t * 2.0 - 2.0) + C2) + 2.0) / 2.0
+                }
+            }
+            Easing::ElasticIn => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    -(2f64.powf(10.0 * t - 10.0)) * ((t * 10.0 - 10.75) * 2.0 * PI / 3.0).sin()
+                }
+            }
+            Easing::ElasticOut => {
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else {
+                    2f64.powf(-10.0 * t) * ((t * 10.0 - 0.75) * 2.0 * PI / 3.0).sin() + 1.0
+                }
+            }
+            Easing::ElasticInOut => {
+                let c5 = 2.0 * PI / 4.5;
+                if t == 0.0 || t == 1.0 {
+                    t
+                } else if t < 0.5 {
+                    -(2f64.powf(20.0 * t - 10.0) * ((20.0 * t - 11.125) * c5).sin()) / 2.0
+                } else {
+                    2f64.powf(-20.0 * t + 10.0) * ((20.0 * t - 11.125) * c5).sin() / 2.0 + 1.0
+                }
+            }
+            Easing::BounceIn => 1.0 - bounce_out(1.0 - t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut => {
+                if t < 0.5 {
+                    (1.0 - bounce_out(1.0 - 2.0 * t)) / 2.0
+                } else {
+                    (1.0 + bounce_out(2.0 * t - 1.0)) / 2.0
+                }
+            }
+        }
+    }
+}
+
+fn bounce_out(t: f64) -> f64 {
+    const N1: f64 = 7.5625;
+    const D1: f64 = 2.75;
+    if t < 1.0 / D1 {
+        N1 * t * t
+    } else if t < 2.0 / D1 {
+        let t = t - 1.5 / D1;
+        N1 * t * t + 0.75
+    } else if t < 2.5 / D1 {
+        let t = t - 2.25 / D1;
+        N1 * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D1;
+        N1 * t * t + 0.984375
+    }
+}
+
+/// Values that can be interpolated. `[f64; 2]` covers both `Point` and `Dimensions`.
+pub trait Lerp: Copy {
+    /// The value `t` of the way from `self` to `to`. `t` may fall outside `0.0..1.0`.
+    fn lerp(&self, to: &Self, t: f64) -> Self;
+}
+impl Lerp for f64 {
+    fn lerp(&self, to: &f64, t: f64) -> f64 {
+        self + (to - self) * t
+    }
+}
+impl Lerp for [f64; 2] {
+    fn lerp(&self, to: &[f64; 2], t: f64) -> [f64; 2] {
+        [self[0].lerp(&to[0], t), self[1].lerp(&to[1], t)]
+    }
+}
+/// Interpolates the RGBA components.
+impl Lerp for Color {
+    fn lerp(&self, to: &Color, t: f64) -> Color {
+        let (a, b) = (self.to_rgb(), to.to_rgb());
+        let l = |x: f32, y: f32| x + (y - x) * t as f32;
+        Color::Rgba(l(a.0, b.0), l(a.1, b.1), l(a.2, b.2), l(a.3, b.3))
+    }
+}
+
+/// A value moving from `from` to `to`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tween<T: Lerp> {
+    pub from: T,
+    pub to: T,
+    pub duration: Duration,
+    /// Time spent at `from` before moving.
+    pub delay: Duration,
+    pub easing: Easing,
+}
+impl<T: Lerp> Tween<T> {
+    pub fn new(from: T, to: T, duration: Duration) -> Self {
+        Tween {
+            from: from,
+            to: to,
+            duration: duration,
+            delay: Duration::new(0, 0),
+            easing: Easing::Linear,
+        }
+    }
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+    pub fn easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+    /// The delay plus the duration.
+    pub fn total(&self) -> Duration {
+        self.delay + self.duration
+    }
+    /// Eased progress after `elapsed`: 0 until the delay is over, 1 from the end on.
+    pub fn progress(&self, elapsed: Duration) -> f64 {
+        if elapsed <= self.delay {
+            return 0.0;
+        }
+        let d = secs(self.duration);
+        if d <= 0.0 {
+            return 1.0;
+        }
+        self.easing.apply(secs(elapsed - self.delay) / d)
+    }
+    /// The value after `elapsed`.
+    pub fn value_at(&self, elapsed: Duration) -> T {
+        self.from.lerp(&self.to, self.progress(elapsed))
+    }
+    pub fn is_finished(&self, elapsed: Duration) -> bool {
+        elapsed >= self.total()
+    }
+}
+
+/// Tweens played back to back, each starting where the previous one ended.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sequence<T: Lerp> {
+    start: T,
+    steps: Vec<Tween<T>>,
+}
+impl<T: Lerp> Sequence<T> {
+    /// An empty sequence resting at `start`.
+    pub fn new(start: T) -> Self {
+        Sequence {
+            start: start,
+            steps: vec![],
+        }
+    }
+    /// Where the sequence ends.
+    pub fn end(&self) -> T {
+        self.steps.last().map_or(self.start, |s| s.to)
+    }
+    /// Move from the current end to `to`.
+    pub fn to(mut self, to: T, duration: Duration, easing: Easing) -> Self {
+        let from = self.end();
+        self.steps.push(Tween::new(from, to, duration).easing(easing));
+        self
+    }
+    /// Hold the current end for `duration`.
+    pub fn wait(mut self, duration: Duration) -> Self {
+        let end = self.end();
+        self.steps.push(Tween::new(end, end, duration));
+        self
+    }
+    /// Append a tween as it is, even if it doesn't start at the current end.
+    pub fn then(mut self, tween: Tween<T>) -> Self {
+        self.steps.push(tween);
+        self
+    }
+    pub fn total(&self) -> Duration {
+        self.steps.iter().fold(Duration::new(0, 0), |t, s| t + s.total())
+    }
+    /// The value after `elapsed`.
+    pub fn value_at(&self, elapsed: Duration) -> T {
+        let mut left = elapsed;
+        for step in self.steps.iter() {
+            if left < step.total() {
+                return step.value_at(left);
+            }
+            left -= step.total();
+        }
+        self.end()
+    }
+    pub fn is_finished(&self, elapsed: Duration) -> bool {
+        elapsed >= self.total()
+    }
+}
+
+/// The point `t` of the way along the quadratic Bézier curve from `p0` to `p2` bent towards `p1`.
+pub fn quadratic_bezier(p0: [f64; 2], p1: [f64; 2], p2: [f64; 2], t: f64) -> [f64; 2] {
+    p0.lerp(&p1, t).lerp(&p1.lerp(&p2, t), t)
+}
+
+/// The point `t` of the way along the cubic Bézier curve from `p0` to `p3` with controls `p1`
+/// and `p2`.
+pub fn cubic_bezier(p0: [f64; 2], p1: [f64; 2], p2: [f64; 2], p3: [f64; 2], t: f64) -> [f64; 2] {
+    quadratic_bezier(p0, p1, p2, t).lerp(&quadratic_bezier(p1, p2, p3, t), t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [Easing; 16] = [Easing::Linear,
+                               Easing::QuadIn,
+                               Easing::QuadOut,
+                               Easing::QuadInOut,
+                               Easing::CubicIn,
+                               Easing::CubicOut,
+                               Easing::CubicInOut,
+                               Easing::BackIn,
+                               Easing::BackOut,
+                               Easing::BackInOut,
+                               Easing::ElasticIn,
+                               Easing::ElasticOut,
+                               Easing::ElasticInOut,
+                               Easing::BounceIn,
+                               Easing::BounceOut,
+                               Easing::BounceInOut];
+
+    fn ms(ms: u64) -> Duration {
+        Duration::from_millis(ms)
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn every_curve_runs_from_0_to_1() {
+        for easing in ALL.iter() {
+            assert!(close(easing.apply(0.0), 0.0), "{:?} at 0", easing);
+            assert!(close(easing.apply(1.0), 1.0), "{:?} at 1", easing);
+            assert!(close(easing.apply(-1.0), 0.0), "{:?} before 0", easing);
+            assert!(close(easing.apply(2.0), 1.0), "{:?} after 1", easing);
+        }
+    }
+
+    #[test]
+    fn back_and_elastic_curves_overshoot() {
+        let samples = |easing: Easing| (1..100).map(move |i| easing.apply(i as f64 / 100.0));
+        assert!(samples(Easing::BackIn).any(|v| v < 0.0));
+        assert!(samples(Easing::BackOut).any(|v| v > 1.0));
+        assert!(samples(Easing::ElasticOut).any(|v| v > 1.0));
+        assert!(samples(Easing::BounceOut).all(|v| v >= 0.0 && v <= 1.0));
+        assert!(samples(Easing::CubicInOut).all(|v| v >= 0.0 && v <= 1.0));
+    }
+
+    #[test]
+    fn tween_waits_for_its_delay() {
+        let tween = Tween::new(10.0, 20.0, ms(100)).delay(ms(50));
+        assert_eq!(tween.total(), ms(150));
+        assert_eq!(tween.value_at(ms(0)), 10.0);
+        assert_eq!(tween.value_at(ms(50)), 10.0);
+        assert!(close(tween.value_at(ms(100)), 15.0));
+        assert_eq!(tween.value_at(ms(500)), 20.0);
+        assert!(!tween.is_finished(ms(149)));
+        assert!(tween.is_finished(ms(150)));
+        assert_eq!(Tween::new(1.0, 2.0, ms(0)).value_at(ms(1)), 2.0);
+    }
+
+    #[test]
+    fn sequence_plays_its_steps_back_to_back() {
+        let sequence = Sequence::new([0.0, 0.0])
+            .to([10.0, 0.0], ms(100), Easing::Linear)
+            .wait(ms(50))
+            .to([10.0, 20.0], ms(100), Easing::Linear);
+        assert_eq!(sequence.total(), ms(250));
+        assert_eq!(sequence.end(), [10.0, 20.0]);
+        assert_eq!(sequence.value_at(ms(0)), [0.0, 0.0]);
+        assert!(close(sequence.value_at(ms(50))[0], 5.0));
+        assert_eq!(sequence.value_at(ms(120)), [10.0, 0.0]);
+        assert!(close(sequence.value_at(ms(200))[1], 10.0));
+        assert_eq!(sequence.value_at(ms(300)), [10.0, 20.0]);
+        assert!(!sequence.is_finished(ms(249)));
+        assert!(sequence.is_finished(ms(250)));
+    }
+}